@@ -1,43 +1,245 @@
 use std::error::Error;
 use std::fs::File;
-use std::io::{stdin, BufRead, BufReader};
+use std::io::{stdin, BufRead, BufReader, BufWriter};
 use std::path::Path;
 use std::string::String;
 
-use goodname::Enumerator;
+use goodname::Denylist;
 use goodname::Lexicon;
+use goodname::Pronounceability;
+use goodname::Stopwords;
+use goodname::Tokenizer;
+use goodname::{Availability, Registry};
+use goodname::{BuildReport, InvalidPolicy, LexiconBuilder};
+use goodname::{Constraint, Enumerator};
 
 use clap::Parser;
 
 #[derive(Parser, Debug)]
 #[clap(name = "goodname-cli", about = "A CLI tool of goodname.")]
 struct Args {
-    #[clap(short = 'w', action)]
-    wordlist_filename: String,
+    #[clap(
+        short = 'w',
+        action,
+        required_unless_present = "lexicon-filename",
+        conflicts_with = "lexicon-filename"
+    )]
+    wordlist_filename: Option<String>,
+
+    #[clap(short = 'i', action)]
+    lexicon_filename: Option<String>,
+
+    #[clap(short = 'o', action)]
+    output_filename: Option<String>,
 
     #[clap(short = 'k', action, default_value = "30")]
     topk: usize,
 
     #[clap(short = 'l', action, default_value = "0")]
     prefix_len: usize,
+
+    #[clap(short = 't', action, default_value = "0")]
+    suffix_len: usize,
+
+    #[clap(short = 'f', action, default_value = "0")]
+    max_insertions: usize,
+
+    #[clap(short = 'F', action, default_value = "0.5")]
+    insertion_penalty: f64,
+
+    #[clap(short = 'r', action, default_value = "0")]
+    weight_ratio: f64,
+
+    #[clap(short = 'p', action, default_value = "0")]
+    pronounceability_ratio: f64,
+
+    #[clap(short = 'q', action, default_value = "0")]
+    min_pronounceability: f64,
+
+    #[clap(short = 'd', action)]
+    display_forms: bool,
+
+    #[clap(short = 'a', action)]
+    accent_insensitive: bool,
+
+    #[clap(short = 'A', action)]
+    anagram: bool,
+
+    #[clap(short = 'C', action)]
+    camel_case: bool,
+
+    #[clap(short = 'g', action, conflicts_with = "stopwords-filename")]
+    english_stopwords: bool,
+
+    #[clap(short = 'G', action)]
+    stopwords_filename: Option<String>,
+
+    #[clap(short = 'e', action, conflicts_with = "output-filename")]
+    extra_wordlists: Vec<String>,
+
+    #[clap(short = 'b', action)]
+    denylist_filename: Option<String>,
+
+    #[clap(short = 'n', action)]
+    registry_filename: Option<String>,
+
+    #[clap(short = 'x', action, requires = "registry-filename")]
+    hide_taken: bool,
+
+    #[clap(short = 'c', action, requires = "registry-filename")]
+    conflict_distance: Option<usize>,
+
+    #[clap(short = 's', action)]
+    start_word: Option<usize>,
+
+    #[clap(short = 'u', action)]
+    use_every_word: bool,
+
+    #[clap(short = 'm', action, default_value = "0")]
+    min_len: usize,
+
+    #[clap(short = 'M', action)]
+    max_len: Option<usize>,
 }
 
+// The maximum number of conflicting names to print for each candidate.
+const MAX_CONFLICTS: usize = 3;
+
 fn main() -> Result<(), Box<dyn Error>> {
     let args = Args::parse();
-    let lex = Lexicon::new(load_lines(&args.wordlist_filename)?)?;
+    let bytes = match &args.lexicon_filename {
+        Some(lexicon_filename) => std::fs::read(lexicon_filename)?,
+        None => vec![],
+    };
+    let lex: Lexicon = if args.lexicon_filename.is_some() {
+        Lexicon::from_bytes(&bytes)?
+    } else {
+        build_lexicon(args.wordlist_filename.as_ref().unwrap())?
+    };
+    let mut extra_lexicons = vec![];
+    for extra_wordlist in &args.extra_wordlists {
+        let (filename, weight) = parse_extra_wordlist(extra_wordlist)?;
+        extra_lexicons.push((filename, build_lexicon(filename)?, weight));
+    }
+    if let Some(output_filename) = &args.output_filename {
+        lex.serialize_into(BufWriter::new(File::create(output_filename)?))?;
+        println!("Wrote the lexicon into {}", output_filename);
+        return Ok(());
+    }
+    let denylist = match &args.denylist_filename {
+        Some(denylist_filename) => Some(Denylist::new(load_lines(denylist_filename)?)),
+        None => None,
+    };
+    let stopwords = match &args.stopwords_filename {
+        Some(stopwords_filename) => Some(Stopwords::new(load_lines(stopwords_filename)?)),
+        None => args.english_stopwords.then(Stopwords::english),
+    };
+    let registry = match &args.registry_filename {
+        Some(registry_filename) => Some(Registry::new(load_lines(registry_filename)?)?),
+        None => None,
+    };
     let prefix_len = args.prefix_len;
+    let model = (args.pronounceability_ratio != 0.0 || args.min_pronounceability != 0.0)
+        .then(|| Pronounceability::new(&lex));
 
     println!("Enter your text:");
     #[allow(clippy::significant_drop_in_scrutinee)]
     for line in stdin().lock().lines() {
         let line = line?;
-        let enumerator = Enumerator::new(&lex, &line)?.prefix_len(prefix_len)?;
-        let matched = enumerator.all_subsequences()?;
-        println!("Matched {} candidates", matched.len());
-        let k = args.topk.min(matched.len());
-        for (i, m) in matched[..k].iter().enumerate() {
+        // Errors in the text, such as invalid markup, are reported without ending the session.
+        let searched = (|| -> Result<_, Box<dyn Error>> {
+            let tokenizer = Tokenizer::new().camel_case(args.camel_case);
+            let mut enumerator = Enumerator::with_tokenizer(&lex, &line, tokenizer)?;
+            if let Some(stopwords) = &stopwords {
+                enumerator = enumerator.stopwords(stopwords)?;
+            }
+            enumerator = enumerator
+                .prefix_len(prefix_len)?
+                .suffix_len(args.suffix_len)?
+                .insertions(args.max_insertions, args.insertion_penalty)?
+                .min_len(args.min_len)?
+                .display_forms(args.display_forms)
+                .accent_insensitive(args.accent_insensitive)
+                .anagram(args.anagram);
+            for (_, extra_lex, weight) in &extra_lexicons {
+                enumerator = enumerator.add_lexicon(extra_lex, *weight)?;
+            }
+            if let Some(denylist) = &denylist {
+                enumerator = enumerator.denylist(denylist);
+            }
+            if let Some(registry) = &registry {
+                enumerator = enumerator.registry(registry).hide_taken(args.hide_taken)?;
+                if let Some(conflict_distance) = args.conflict_distance {
+                    enumerator = enumerator.conflicts(conflict_distance, MAX_CONFLICTS)?;
+                }
+            }
+            if let Some(max_len) = args.max_len {
+                enumerator = enumerator.max_len(max_len)?;
+            }
+            if let Some(start_word) = args.start_word {
+                let i = start_word
+                    .checked_sub(1)
+                    .ok_or("The word to start with must be counted from 1.")?;
+                enumerator = enumerator.constraint(Constraint::StartsWith(i))?;
+            }
+            if args.use_every_word {
+                enumerator = enumerator.constraint(Constraint::UseEveryWord)?;
+            }
+            if args.weight_ratio != 0.0 {
+                enumerator = enumerator.weight_ratio(args.weight_ratio)?;
+            }
+            if let Some(model) = &model {
+                enumerator = enumerator
+                    .pronounceability(model)
+                    .pronounceability_ratio(args.pronounceability_ratio)?
+                    .min_pronounceability(args.min_pronounceability)?;
+            }
+            let matched = enumerator.top_k(args.topk)?;
+            Ok((enumerator, matched))
+        })();
+        let (enumerator, matched) = match searched {
+            Ok(searched) => searched,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                println!("Enter your text:");
+                continue;
+            }
+        };
+        println!("Top-{} candidates", matched.len());
+        for (i, m) in matched.iter().enumerate() {
             let (word, desc) = enumerator.format_match(m);
-            println!("{:>4} {}: {} (score={})", i + 1, word, desc, m.score);
+            let mut info = format!("score={}", m.score);
+            if let Some(p) = m.pronounceability {
+                info += &format!(", pronounceability={:.3}", p);
+            }
+            if let Some(availability) = m.availability {
+                let availability = match availability {
+                    Availability::Taken => "taken",
+                    Availability::Similar => "similar",
+                    Availability::Free => "free",
+                };
+                info += &format!(", {}", availability);
+            }
+            if !m.conflicts.is_empty() {
+                let conflicts: Vec<_> = m
+                    .conflicts
+                    .iter()
+                    .map(|c| format!("{}({})", c.name, c.distance))
+                    .collect();
+                info += &format!(", conflicts={}", conflicts.join("/"));
+            }
+            if !extra_lexicons.is_empty() {
+                let source = match m.lexicon_id {
+                    0 => args
+                        .wordlist_filename
+                        .as_ref()
+                        .or(args.lexicon_filename.as_ref())
+                        .unwrap(),
+                    i => extra_lexicons[i - 1].0,
+                };
+                info += &format!(", source={}", source);
+            }
+            println!("{:>4} {}: {} ({})", i + 1, word, desc, info);
         }
         println!("Enter your text:");
     }
@@ -53,3 +255,59 @@ where
     let buf = BufReader::new(file);
     Ok(buf.lines().map(|line| line.unwrap()).collect())
 }
+
+fn build_lexicon(filename: &str) -> Result<Lexicon<'static>, Box<dyn Error>> {
+    let lines = load_lines(filename)?;
+    let builder = LexiconBuilder::new()
+        .invalid_policy(InvalidPolicy::Drop)
+        .display_forms(true);
+    let (lex, report) = if lines.iter().any(|line| line.contains('\t')) {
+        builder.build_with_weights(parse_weighted_lines(&lines)?)?
+    } else {
+        builder.build(&lines)?
+    };
+    if !report.is_unchanged() {
+        print_report(&report);
+    }
+    Ok(lex)
+}
+
+// Parses `FILE` or `FILE:WEIGHT` into the file name and weight (default: 1).
+fn parse_extra_wordlist(arg: &str) -> Result<(&str, f64), Box<dyn Error>> {
+    match arg.rsplit_once(':') {
+        Some((filename, weight)) => Ok((filename, weight.parse()?)),
+        None => Ok((arg, 1.0)),
+    }
+}
+
+fn print_report(report: &BuildReport) {
+    println!(
+        "Normalized the word list: {} empty lines skipped, {} words trimmed, {} words lowercased, {} duplicates merged, {} words transliterated, {} words dropped{}",
+        report.num_empty,
+        report.num_trimmed,
+        report.num_lowercased,
+        report.num_duplicates,
+        report.transliterated.len(),
+        report.dropped.len(),
+        if report.sorted { ", and sorted" } else { "" }
+    );
+    for spelling in &report.dropped {
+        println!("  Dropped {}", spelling);
+    }
+}
+
+fn parse_weighted_lines(lines: &[String]) -> Result<Vec<(&str, f64)>, Box<dyn Error>> {
+    let mut records = Vec::with_capacity(lines.len());
+    for line in lines {
+        // Blank lines are passed to be skipped and counted by the builder.
+        if line.trim().is_empty() {
+            records.push((line.as_str(), 0.0));
+            continue;
+        }
+        let (word, weight) = line
+            .split_once('\t')
+            .ok_or_else(|| format!("A weight must follow a tab ({}).", line))?;
+        records.push((word, weight.parse()?));
+    }
+    Ok(records)
+}
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use goodname::{Enumerator, Lexicon};
+use goodname::{Denylist, Enumerator, Lexicon, LimitPolicy};
 use once_cell::sync::Lazy;
 use yew::prelude::*;
 
@@ -7,14 +7,14 @@ use crate::cand_view::CandView;
 use crate::range_input::RangeInput;
 use crate::text_input::TextInput;
 
-static LEXICON: Lazy<Lexicon> = Lazy::new(|| {
-    let words = include_str!("words.txt");
-    Lexicon::new(words.split('\n').filter(|w| !w.is_empty())).unwrap()
-});
+static LEXICON: Lazy<Lexicon<'static>> =
+    Lazy::new(|| Lexicon::from_bytes(include_bytes!("lexicon.bin")).unwrap());
 
 pub enum Msg {
     SetText(String),
     SetPrefixLen(String),
+    SetSuffixLen(String),
+    SetDenylist(String),
     GenCandidates,
 }
 
@@ -38,9 +38,11 @@ impl Default for MatchCase {
 pub struct App {
     text: String,
     prefix_len: String,
+    suffix_len: String,
+    denylist: String,
     match_case: MatchCase,
     num_matched: usize,
-    candidates: Vec<(String, String, usize)>,
+    candidates: Vec<(String, String, u64)>,
 }
 
 impl Default for App {
@@ -48,6 +50,8 @@ impl Default for App {
         Self {
             text: "".to_string(),
             prefix_len: "0".to_string(),
+            suffix_len: "0".to_string(),
+            denylist: "".to_string(),
             match_case: MatchCase::NotYet,
             num_matched: 0,
             candidates: vec![],
@@ -72,20 +76,29 @@ impl App {
     }
 
     fn enumurate(&mut self) -> Result<()> {
+        let denylist = Denylist::new(self.denylist.split_whitespace());
         let enumerator = Enumerator::new(&LEXICON, &self.text)?;
-        let enumerator = enumerator.prefix_len(self.prefix_len.parse()?)?;
-        let matched = enumerator.all_subsequences()?;
-        self.num_matched = matched.len();
-        if self.num_matched == 0 {
+        let enumerator = enumerator
+            .prefix_len(self.prefix_len.parse()?)?
+            .suffix_len(self.suffix_len.parse()?)?
+            .denylist(&denylist)
+            // Searches one more than the printed ones to know if there are more than 100 candidates.
+            .max_matches(101)?
+            .limit_policy(LimitPolicy::KeepBest);
+        let mut matched = enumerator.all_subsequences()?;
+        self.num_matched = matched.matches.len();
+        if !matched.complete || 100 < self.num_matched {
+            self.match_case = MatchCase::Over100;
+        } else if self.num_matched == 0 {
             self.match_case = MatchCase::NotMatch;
         } else if self.num_matched <= 10 {
             self.match_case = MatchCase::Under10;
-        } else if self.num_matched <= 100 {
-            self.match_case = MatchCase::Under100;
         } else {
-            self.match_case = MatchCase::Over100;
+            self.match_case = MatchCase::Under100;
         }
-        self.candidates = matched[..matched.len().min(100)]
+        matched.matches.truncate(100);
+        self.candidates = matched
+            .matches
             .iter()
             .map(|m| {
                 let (word, desc) = enumerator.format_match(m);
@@ -109,6 +122,8 @@ impl Component for App {
         match msg {
             Msg::SetText(text) => self.text = text,
             Msg::SetPrefixLen(prefix_len) => self.prefix_len = prefix_len,
+            Msg::SetSuffixLen(suffix_len) => self.suffix_len = suffix_len,
+            Msg::SetDenylist(denylist) => self.denylist = denylist,
             Msg::GenCandidates => self.gen_candidates(),
         };
         true
@@ -158,7 +173,7 @@ impl Component for App {
                             {"Enter a brief description of your method or software:"}
                         </div>
                         <div>
-                            <TextInput on_change={ctx.link().callback(Msg::SetText)} value={self.text.clone()} name="yourdesc" />
+                            <TextInput on_change={ctx.link().callback(Msg::SetText)} value={self.text.clone()} name="yourdesc" placeholder="e.g., Character wise double array dictionary" />
                         </div>
                         <div>
                             {"Set the maximum number of arbitrary prefix letters to allow for generating recursive acronyms: "}
@@ -167,6 +182,19 @@ impl Component for App {
                             <RangeInput on_change={ctx.link().callback(Msg::SetPrefixLen)} value={self.prefix_len.clone()} name="prefix" />
                         <div>
                         </div>
+                        <div>
+                            {"Set the maximum number of arbitrary suffix letters to append to acronyms: "}
+                        </div>
+                            <label class="range" for="suffix">{self.suffix_len.clone()}</label>
+                            <RangeInput on_change={ctx.link().callback(Msg::SetSuffixLen)} value={self.suffix_len.clone()} name="suffix" />
+                        <div>
+                        </div>
+                        <div>
+                            {"Enter words never to propose, separated by spaces (wildcards * and ? are available):"}
+                        </div>
+                        <div>
+                            <TextInput on_change={ctx.link().callback(Msg::SetDenylist)} value={self.denylist.clone()} name="denylist" placeholder="e.g., cheddar craw*" />
+                        </div>
                         <div>
                             <button onclick={ctx.link().callback(|_| Msg::GenCandidates)}>
                                 {"Search"}
@@ -208,7 +236,7 @@ impl Component for App {
                             MatchCase::Over100 => html! {
                                 <div class="candidates">
                                     <div class="nummatches">
-                                        {format!("#matches = {} (Only the top-100 candidates are printed.)", num_matched)}
+                                        {"#matches > 100 (Only the top-100 candidates are printed.)"}
                                     </div>
                                     <div class="toomany-hint">
                                         {"Too many? If so, edit your input by shortening the description, specifying more UPPERCASE letters, etc."}
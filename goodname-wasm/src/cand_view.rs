@@ -2,7 +2,7 @@ use yew::{function_component, html, Properties};
 
 #[derive(Clone, PartialEq, Eq, Properties)]
 pub struct Props {
-    pub candidates: Vec<(String, String, usize)>,
+    pub candidates: Vec<(String, String, u64)>,
 }
 
 #[function_component(CandView)]
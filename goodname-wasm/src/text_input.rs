@@ -7,6 +7,7 @@ pub struct Props {
     pub value: String,
     pub on_change: Callback<String>,
     pub name: String,
+    pub placeholder: String,
 }
 
 fn get_value_from_input_event(e: InputEvent) -> String {
@@ -24,6 +25,7 @@ pub fn text_input(props: &Props) -> Html {
         value,
         on_change,
         name,
+        placeholder,
     } = props.clone();
 
     let oninput = Callback::from(move |input_event: InputEvent| {
@@ -31,6 +33,6 @@ pub fn text_input(props: &Props) -> Html {
     });
 
     html! {
-        <input type="text" name={name} placeholder="e.g., Character wise double array dictionary" {value} {oninput} />
+        <input type="text" name={name} {placeholder} {value} {oninput} />
     }
 }
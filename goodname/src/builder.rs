@@ -0,0 +1,376 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use unicode_normalization::{char::is_combining_mark, is_nfc, UnicodeNormalization};
+
+use crate::Lexicon;
+
+/// Policy for words containing invalid characters, i.e., whitespace and control characters,
+/// and also non-ASCII ones if [`LexiconBuilder::ascii_only`] is enabled.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum InvalidPolicy {
+    /// Returns an error.
+    #[default]
+    Error,
+    /// Drops the words.
+    Drop,
+    /// Transliterates the words into ASCII (e.g., `café` into `cafe`),
+    /// dropping those that cannot be.
+    Transliterate,
+}
+
+/// Report of the normalization by [`LexiconBuilder`].
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct BuildReport {
+    /// The number of empty lines skipped.
+    pub num_empty: usize,
+    /// The number of words with surrounding whitespace trimmed.
+    pub num_trimmed: usize,
+    /// The number of words lowercased.
+    pub num_lowercased: usize,
+    /// The number of words merged into an identical one.
+    pub num_duplicates: usize,
+    /// Whether the words had to be sorted.
+    pub sorted: bool,
+    /// The pairs of the original spellings and the transliterated words.
+    pub transliterated: Vec<(String, String)>,
+    /// The original spellings of the words dropped.
+    pub dropped: Vec<String>,
+    spellings: HashMap<String, usize>,
+}
+
+impl BuildReport {
+    /// Gets the word identifier assigned to the original spelling, if not dropped.
+    ///
+    /// This is available only if [`LexiconBuilder::word_ids`] is enabled.
+    pub fn word_id(&self, spelling: &str) -> Option<usize> {
+        self.spellings.get(spelling).copied()
+    }
+
+    /// Checks if the normalization changed nothing.
+    pub const fn is_unchanged(&self) -> bool {
+        self.num_empty == 0
+            && self.num_trimmed == 0
+            && self.num_lowercased == 0
+            && self.num_duplicates == 0
+            && !self.sorted
+            && self.transliterated.is_empty()
+            && self.dropped.is_empty()
+    }
+}
+
+/// Builder of [`Lexicon`] from raw word lists.
+///
+/// Whereas [`Lexicon::new`] requires sorted, unique, and lower-case words,
+/// the builder normalizes the input as follows:
+///
+///  - trims surrounding whitespace and skips empty lines,
+///  - lowercases the words and normalizes them into NFC,
+///  - handles words with invalid characters in [`InvalidPolicy`], and
+///  - sorts and deduplicates the words, where duplicates take the maximum weight.
+///
+/// Optionally, the original spellings can be kept as the display forms of the words
+/// (see [`Lexicon::display_forms`]).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct LexiconBuilder {
+    invalid_policy: InvalidPolicy,
+    ascii_only: bool,
+    display_forms: bool,
+    word_ids: bool,
+}
+
+impl LexiconBuilder {
+    /// Creates an instance.
+    pub const fn new() -> Self {
+        Self {
+            invalid_policy: InvalidPolicy::Error,
+            ascii_only: false,
+            display_forms: false,
+            word_ids: false,
+        }
+    }
+
+    /// Specifies the policy for words with invalid characters (default: [`InvalidPolicy::Error`]).
+    pub const fn invalid_policy(mut self, invalid_policy: InvalidPolicy) -> Self {
+        self.invalid_policy = invalid_policy;
+        self
+    }
+
+    /// Specifies whether to regard non-ASCII characters as invalid (default: `false`).
+    pub const fn ascii_only(mut self, yes: bool) -> Self {
+        self.ascii_only = yes;
+        self
+    }
+
+    /// Specifies whether to keep the original spellings (e.g., `GitHub` for `github`)
+    /// as the display forms (default: `false`).
+    /// If a word has several spellings, the first one differing from the word is kept.
+    pub const fn display_forms(mut self, yes: bool) -> Self {
+        self.display_forms = yes;
+        self
+    }
+
+    /// Specifies whether to record the word identifiers assigned to the original spellings,
+    /// obtained by [`BuildReport::word_id`] (default: `false`).
+    pub const fn word_ids(mut self, yes: bool) -> Self {
+        self.word_ids = yes;
+        self
+    }
+
+    /// Builds the lexicon from a set of words.
+    pub fn build<I, W>(&self, words: I) -> Result<(Lexicon<'static>, BuildReport)>
+    where
+        I: IntoIterator<Item = W>,
+        W: AsRef<str>,
+    {
+        let (records, displays, report) = self.normalize(words.into_iter().map(|w| (w, 0.0)))?;
+        let lex = Lexicon::new(records.iter().map(|(word, _)| word))?;
+        Ok((self.attach_display_forms(lex, &displays)?, report))
+    }
+
+    /// Builds the lexicon from a set of words with weights, as in [`Lexicon::with_weights`].
+    pub fn build_with_weights<I, W>(&self, records: I) -> Result<(Lexicon<'static>, BuildReport)>
+    where
+        I: IntoIterator<Item = (W, f64)>,
+        W: AsRef<str>,
+    {
+        let (records, displays, report) = self.normalize(records)?;
+        let lex = Lexicon::with_weights(records)?;
+        Ok((self.attach_display_forms(lex, &displays)?, report))
+    }
+
+    fn attach_display_forms(
+        &self,
+        lex: Lexicon<'static>,
+        displays: &HashMap<String, String>,
+    ) -> Result<Lexicon<'static>> {
+        if !self.display_forms || displays.is_empty() {
+            return Ok(lex);
+        }
+        let forms: Vec<_> = (0..lex.num_words())
+            .map(|word_id| {
+                let word = lex.word(word_id);
+                displays.get(word).map_or(word, String::as_str).to_string()
+            })
+            .collect();
+        lex.display_forms(forms)
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn normalize<I, W>(
+        &self,
+        records: I,
+    ) -> Result<(Vec<(String, f64)>, HashMap<String, String>, BuildReport)>
+    where
+        I: IntoIterator<Item = (W, f64)>,
+        W: AsRef<str>,
+    {
+        let mut report = BuildReport::default();
+        let mut normalized: Vec<(String, f64)> = vec![];
+        let mut spellings = vec![];
+        let mut displays = HashMap::new();
+        for (spelling, weight) in records {
+            let spelling = spelling.as_ref();
+            let trimmed = spelling.trim();
+            if trimmed.is_empty() {
+                report.num_empty += 1;
+                continue;
+            }
+            if trimmed.len() != spelling.len() {
+                report.num_trimmed += 1;
+            }
+            let lower = trimmed.to_lowercase();
+            if lower != trimmed {
+                report.num_lowercased += 1;
+            }
+            let mut word = if is_nfc(&lower) {
+                lower
+            } else {
+                lower.nfc().collect()
+            };
+            if !self.is_valid(&word) {
+                match self.invalid_policy {
+                    InvalidPolicy::Error => {
+                        return Err(anyhow!(
+                            "Input words must not contain invalid characters ({}).",
+                            spelling
+                        ));
+                    }
+                    InvalidPolicy::Drop => {
+                        report.dropped.push(spelling.to_string());
+                        continue;
+                    }
+                    InvalidPolicy::Transliterate => {
+                        if let Some(ascii) = transliterate(&word).filter(|w| self.is_valid(w)) {
+                            report
+                                .transliterated
+                                .push((spelling.to_string(), ascii.clone()));
+                            word = ascii;
+                        } else {
+                            report.dropped.push(spelling.to_string());
+                            continue;
+                        }
+                    }
+                }
+            }
+            if normalized.last().is_some_and(|(prev, _)| prev > &word) {
+                report.sorted = true;
+            }
+            if self.display_forms && trimmed != word && !displays.contains_key(&word) {
+                displays.insert(word.clone(), trimmed.to_string());
+            }
+            if self.word_ids {
+                spellings.push((spelling.to_string(), word.clone()));
+            }
+            normalized.push((word, weight));
+        }
+        // The stable sort is fast for the sorted input.
+        if report.sorted {
+            normalized.sort_by(|(w1, _), (w2, _)| w1.cmp(w2));
+        }
+        normalized.dedup_by(|(word, weight), (kept, max)| {
+            if word != kept {
+                return false;
+            }
+            report.num_duplicates += 1;
+            if *max < *weight {
+                *max = *weight;
+            }
+            true
+        });
+        if self.word_ids {
+            report.spellings = spellings
+                .into_iter()
+                .map(|(spelling, word)| {
+                    let word_id = normalized.binary_search_by(|(w, _)| w.cmp(&word)).unwrap();
+                    (spelling, word_id)
+                })
+                .collect();
+        }
+        Ok((normalized, displays, report))
+    }
+
+    fn is_valid(&self, word: &str) -> bool {
+        word.chars()
+            .all(|c| !c.is_whitespace() && !c.is_control() && (c.is_ascii() || !self.ascii_only))
+    }
+}
+
+// Decomposes accented letters and strips the marks, also expanding some ligatures.
+fn transliterate(word: &str) -> Option<String> {
+    let mut ascii = String::with_capacity(word.len());
+    for c in word.nfkd().filter(|&c| !is_combining_mark(c)) {
+        match c {
+            'ß' => ascii.push_str("ss"),
+            'æ' => ascii.push_str("ae"),
+            'œ' => ascii.push_str("oe"),
+            'ø' => ascii.push('o'),
+            'ł' => ascii.push('l'),
+            'đ' | 'ð' => ascii.push('d'),
+            'þ' => ascii.push_str("th"),
+            'ı' => ascii.push('i'),
+            c if c.is_ascii() => ascii.push(c),
+            _ => return None,
+        }
+    }
+    Some(ascii)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        let words = ["bb", "Ab", "", "aa", "  bb", "ab", "a b"];
+        assert!(LexiconBuilder::new().build(words).is_err());
+
+        let builder = LexiconBuilder::new()
+            .invalid_policy(InvalidPolicy::Drop)
+            .word_ids(true);
+        let (lex, report) = builder.build(words).unwrap();
+        assert_eq!(lex.num_words(), 3);
+        assert_eq!(lex.word(0), "aa");
+        assert_eq!(lex.word(1), "ab");
+        assert_eq!(lex.word(2), "bb");
+        assert_eq!(report.num_empty, 1);
+        assert_eq!(report.num_trimmed, 1);
+        assert_eq!(report.num_lowercased, 1);
+        assert_eq!(report.num_duplicates, 2);
+        assert!(report.sorted);
+        assert_eq!(report.dropped, vec!["a b".to_string()]);
+        assert_eq!(report.word_id("Ab"), Some(1));
+        assert_eq!(report.word_id("  bb"), Some(2));
+        assert_eq!(report.word_id("a b"), None);
+        assert!(!report.is_unchanged());
+
+        let (_, report) = builder.build(["aa", "ab", "bb"]).unwrap();
+        assert!(report.is_unchanged());
+        let (_, report) = LexiconBuilder::new().build(["aa", "ab"]).unwrap();
+        assert_eq!(report.word_id("aa"), None);
+    }
+
+    #[test]
+    fn test_build_display_forms() {
+        let words = ["github", "GitHub", "Word", "iphone", "iPhone"];
+        let (lex, _) = LexiconBuilder::new().build(words).unwrap();
+        assert_eq!(lex.display(0), "github");
+
+        let builder = LexiconBuilder::new().display_forms(true);
+        let (lex, _) = builder.build(words).unwrap();
+        assert_eq!(lex.word(0), "github");
+        assert_eq!(lex.display(0), "GitHub");
+        assert_eq!(lex.display(1), "iPhone");
+        assert_eq!(lex.display(2), "Word");
+    }
+
+    #[test]
+    fn test_build_unicode() {
+        let words = ["Équipe", "straße", "e\u{301}quipe"];
+        let builder = LexiconBuilder::new().word_ids(true);
+        let (lex, report) = builder.build(words).unwrap();
+        assert_eq!(lex.num_words(), 2);
+        assert_eq!(lex.word(0), "straße");
+        assert_eq!(lex.word(1), "équipe");
+        assert_eq!(report.num_duplicates, 1);
+        assert_eq!(report.word_id("e\u{301}quipe"), Some(1));
+
+        assert!(LexiconBuilder::new().ascii_only(true).build(words).is_err());
+    }
+
+    #[test]
+    fn test_build_transliterate() {
+        let builder = LexiconBuilder::new()
+            .ascii_only(true)
+            .invalid_policy(InvalidPolicy::Transliterate)
+            .word_ids(true);
+        let (lex, report) = builder.build(["Café", "straße", "cafe", "日本"]).unwrap();
+        assert_eq!(lex.num_words(), 2);
+        assert_eq!(lex.word(0), "cafe");
+        assert_eq!(lex.word(1), "strasse");
+        assert_eq!(
+            report.transliterated,
+            vec![
+                ("Café".to_string(), "cafe".to_string()),
+                ("straße".to_string(), "strasse".to_string())
+            ]
+        );
+        assert_eq!(report.dropped, vec!["日本".to_string()]);
+        assert_eq!(report.word_id("Café"), Some(0));
+        assert_eq!(report.word_id("cafe"), Some(0));
+    }
+
+    #[test]
+    fn test_build_with_weights() {
+        let builder = LexiconBuilder::new();
+        let (lex, report) = builder
+            .build_with_weights([("b", 1.0), ("a", 2.0), ("", 0.0), ("B", 3.0)])
+            .unwrap();
+        assert_eq!(lex.word(0), "a");
+        assert_eq!(lex.weight(0), Some(2.0));
+        assert_eq!(lex.word(1), "b");
+        assert_eq!(lex.weight(1), Some(3.0));
+        assert_eq!(report.num_duplicates, 1);
+        assert_eq!(report.num_empty, 1);
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::HashSet;
+
+use unicode_normalization::UnicodeNormalization;
+
+// Wildcards matching any sequence of letters and any single letter, respectively.
+const ANY_SEQ: char = '*';
+const ANY_ONE: char = '?';
+
+/// Set of words never to propose as candidates, such as offensive or reserved ones.
+///
+/// Each entry is either a word or a glob pattern with wildcards `*` (any sequence of letters)
+/// and `?` (any single letter), e.g., `bad*` denies all the words starting with `bad`.
+/// Entries are matched case-insensitively.
+///
+/// Given to [`Enumerator::denylist`](crate::Enumerator::denylist), denied words are discarded
+/// when reached in the lexicon, so they never appear nor count toward the limit of candidates.
+#[derive(Clone, Debug, Default)]
+pub struct Denylist {
+    words: HashSet<String>,
+    patterns: Vec<Vec<char>>,
+}
+
+impl Denylist {
+    /// Creates an instance from a set of entries,
+    /// skipping empty ones and comments starting with `#`, such as lines of a file.
+    pub fn new<I, W>(entries: I) -> Self
+    where
+        I: IntoIterator<Item = W>,
+        W: AsRef<str>,
+    {
+        let mut words = HashSet::new();
+        let mut patterns = vec![];
+        for entry in entries {
+            let entry = entry.as_ref().trim();
+            if entry.is_empty() || entry.starts_with('#') {
+                continue;
+            }
+            let entry: String = entry.to_lowercase().nfc().collect();
+            if entry.contains([ANY_SEQ, ANY_ONE]) {
+                patterns.push(entry.chars().collect());
+            } else {
+                words.insert(entry);
+            }
+        }
+        Self { words, patterns }
+    }
+
+    /// Gets the number of entries.
+    pub fn len(&self) -> usize {
+        self.words.len() + self.patterns.len()
+    }
+
+    /// Checks if there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks if the word is denied.
+    pub fn contains(&self, word: &str) -> bool {
+        if self.words.contains(word) {
+            return true;
+        }
+        if self.patterns.is_empty() {
+            return false;
+        }
+        let word: Vec<_> = word.chars().collect();
+        self.patterns
+            .iter()
+            .any(|pattern| Self::glob_match(pattern, &word))
+    }
+
+    fn glob_match(pattern: &[char], word: &[char]) -> bool {
+        let (mut i, mut j) = (0, 0);
+        // The positions just after the last `*` and of the word letter it is matched up to.
+        let mut backtrack = None;
+        while j < word.len() {
+            if i < pattern.len() && pattern[i] == ANY_SEQ {
+                i += 1;
+                backtrack = Some((i, j));
+            } else if i < pattern.len() && (pattern[i] == ANY_ONE || pattern[i] == word[j]) {
+                i += 1;
+                j += 1;
+            } else if let Some((bi, bj)) = backtrack {
+                i = bi;
+                j = bj + 1;
+                backtrack = Some((bi, bj + 1));
+            } else {
+                return false;
+            }
+        }
+        pattern[i..].iter().all(|&c| c == ANY_SEQ)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_denylist() {
+        let denylist = Denylist::new(["# comment", "", "Bad", "*ugly*", "n?sty", "wor*d"]);
+        assert_eq!(denylist.len(), 4);
+        assert!(denylist.contains("bad"));
+        assert!(!denylist.contains("badly"));
+        assert!(denylist.contains("ugly"));
+        assert!(denylist.contains("smugly"));
+        assert!(denylist.contains("nasty"));
+        assert!(!denylist.contains("nsty"));
+        assert!(denylist.contains("word"));
+        assert!(denylist.contains("world"));
+        assert!(!denylist.contains("words"));
+        assert!(!denylist.contains("# comment"));
+        assert!(Denylist::default().is_empty());
+    }
+}
@@ -1,22 +1,88 @@
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap};
 use std::num::NonZeroU8;
+use std::ops::{AddAssign, Range, Sub};
 
 use anyhow::{anyhow, Result};
+use unicode_normalization::{is_nfc, UnicodeNormalization};
 
-use crate::utils;
+use crate::denylist::Denylist;
+use crate::pronounceability::Pronounceability;
+use crate::registry::{Availability, Conflict, Registry};
+use crate::scorer::{Candidate, PositionalScorer, Scorer};
+use crate::stopwords::Stopwords;
+use crate::tokenizer::Tokenizer;
+use crate::utils::{self, DELIMITER};
 use crate::{trie::Trie, Lexicon};
 
-const DELIMITER: u8 = b' ';
 const MAX_MATCHES: usize = 10000;
-const MAX_PREFIX_LEN: usize = 3;
+const MAX_AFFIX_LEN: usize = 3;
+const MAX_ANAGRAM_WORDS: usize = 8;
+const MAX_BOOST: u64 = 16;
 
+// Markup following a word in the text, such as `character^2`, `double!`, and `array?`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Annotation {
+    // The factor multiplied to the position scores of the word, given by `^N`.
+    boost: u64,
+    // Whether to take at least one letter from the word, given by `!`.
+    required: bool,
+    // Whether the UPPERCASE letters of the word can be skipped even with
+    // Constraint::UseEveryWord, given by `?`.
+    optional: bool,
+}
+
+impl Default for Annotation {
+    fn default() -> Self {
+        Self {
+            boost: 1,
+            required: false,
+            optional: false,
+        }
+    }
+}
+
+impl Annotation {
+    /// Parses the markup at the beginning of the text following a word.
+    fn parse(text: &str) -> Result<Self> {
+        let mut annotation = Self::default();
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '!' => annotation.required = true,
+                '?' => annotation.optional = true,
+                '^' => {
+                    let mut digits = String::new();
+                    while let Some(d) = chars.next_if(char::is_ascii_digit) {
+                        digits.push(d);
+                    }
+                    annotation.boost = digits
+                        .parse()
+                        .ok()
+                        .filter(|boost| (1..=MAX_BOOST).contains(boost))
+                        .ok_or_else(|| {
+                            anyhow!("the boost of each word must be in 1..={}.", MAX_BOOST)
+                        })?;
+                }
+                _ => break,
+            }
+        }
+        if annotation.required && annotation.optional {
+            return Err(anyhow!("a word cannot be both required and optional."));
+        }
+        Ok(annotation)
+    }
+}
+
+// Arbitrary letters added before or after the acronym.
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
-struct Prefix([Option<NonZeroU8>; MAX_PREFIX_LEN + 1]);
+struct Affix([Option<NonZeroU8>; MAX_AFFIX_LEN + 1]);
 
-impl Prefix {
+impl Affix {
     #[inline(always)]
-    fn new() -> Self {
-        Self::default()
+    const fn new() -> Self {
+        Self([None; MAX_AFFIX_LEN + 1])
     }
 
     #[inline(always)]
@@ -27,7 +93,7 @@ impl Prefix {
     #[inline(always)]
     fn push(mut self, c: u8) -> Self {
         let i = self.len();
-        debug_assert!(i < MAX_PREFIX_LEN);
+        debug_assert!(i < MAX_AFFIX_LEN);
         self.0[i] = NonZeroU8::new(c);
         self
     }
@@ -42,306 +108,1378 @@ impl Prefix {
     }
 }
 
+#[derive(Clone, Copy)]
 struct State {
     node_pos: u32,
     text_pos: usize,
-    score: usize,
-    positions: u128,
-    prefix: Prefix,
+    score: u64,
+    // The number of active positions, including the last one if consumed.
+    num_positions: usize,
+    // Whether the letter at text_pos - 1 is consumed by the last transition.
+    consumed: bool,
+    prefix: Affix,
+    suffix: Affix,
+    // The number of bytes of the word so far.
+    depth: usize,
+    // The number of inserted letters, including the last one if inserted.
+    num_insertions: usize,
+    // Whether the last transition inserts a letter, which must be followed by a consumed one.
+    inserted: bool,
+    // The current word and the set of words visited so far, in the anagram mode,
+    // indexed among the words except for the stopwords.
+    segment: usize,
+    visited: u64,
 }
 
 impl State {
     #[inline(always)]
-    const fn new(
-        node_pos: u32,
-        text_pos: usize,
-        score: usize,
-        positions: u128,
-        prefix: Prefix,
-    ) -> Self {
+    const fn root() -> Self {
         Self {
-            node_pos,
-            text_pos,
-            score,
-            positions,
-            prefix,
+            node_pos: Trie::root_pos(),
+            text_pos: 0,
+            score: 0,
+            num_positions: 0,
+            consumed: false,
+            prefix: Affix::new(),
+            suffix: Affix::new(),
+            depth: 0,
+            num_insertions: 0,
+            inserted: false,
+            segment: 0,
+            visited: 0,
         }
     }
 }
 
+/// The active positions, inserted letters, and word order on the path to the current state.
+#[derive(Default)]
+struct Path {
+    positions: Vec<usize>,
+    insertions: Vec<usize>,
+    order: Vec<usize>,
+}
+
 /// A resultant candidate.
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct Match {
     /// The word identifier assigned by [`Lexicon`].
     pub word_id: usize,
+    /// The lexicon identifier, i.e., 0 for the one given in [`Enumerator::new`]
+    /// and `i` for the `i`-th one added with [`Enumerator::add_lexicon`].
+    pub lexicon_id: usize,
     /// The goodnamely score.
-    pub score: usize,
-    /// The set of positions where the letters are active.
-    pub positions: u128,
+    pub score: u64,
+    /// The positions where the letters are active, in ascending order.
+    pub positions: Vec<usize>,
     /// The prefix letters for recursive acronym.
     pub prefix: String,
+    /// The suffix letters appended to the acronym.
+    pub suffix: String,
+    /// The byte offsets of the letters inserted into the acronym in the word, in ascending order.
+    pub insertions: Vec<usize>,
+    /// The order of the words in the text forming the acronym, in the anagram mode enabled with
+    /// [`Enumerator::anagram`] (or empty otherwise).
+    pub order: Vec<usize>,
+    /// The pronounceability of the word, if a model is given with [`Enumerator::pronounceability`].
+    pub pronounceability: Option<f64>,
+    /// The availability of the word, if a registry is given with [`Enumerator::registry`].
+    pub availability: Option<Availability>,
+    /// The registered names close to the word, if requested with [`Enumerator::conflicts`].
+    /// They are found only for the candidates of [`Enumerator::all_subsequences`] and [`Enumerator::top_k`].
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Policy of what to do when the number of candidates exceeds the maximum.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum LimitPolicy {
+    /// Fails with an error.
+    #[default]
+    Error,
+    /// Stops the search and returns the candidates found so far.
+    Truncate,
+    /// Continues the search and keeps only the best candidates.
+    KeepBest,
+}
+
+/// Constraint on candidates, given to [`Enumerator::constraint`].
+///
+/// Words are the runs of letters split by the [`Tokenizer`] (e.g., at spaces, hyphens, punctuation,
+/// and digits, and optionally at camelCase boundaries), identified from 0 in the text including the stopwords.
+/// Constraints are checked while traversing the lexicon, so the states violating them are never expanded.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Constraint {
+    /// The first letter from the text must be the first one of the given word,
+    /// i.e., no letters are taken from the preceding words.
+    StartsWith(usize),
+    /// At least one letter must be taken from the given word.
+    UseWord(usize),
+    /// At least one letter must be taken from every word.
+    UseEveryWord,
+    /// The candidate must consist of at least the given number of letters, including the prefix and suffix.
+    MinLength(usize),
+    /// The candidate must consist of at most the given number of letters, including the prefix and suffix.
+    MaxLength(usize),
+}
+
+/// Resultant candidates of [`Enumerator::all_subsequences`].
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Matches {
+    /// The candidates in score order.
+    pub matches: Vec<Match>,
+    /// `false` if some candidates may have been dropped because of the limit.
+    pub complete: bool,
+}
+
+// Lexicon to search.
+struct Source<'a> {
+    lex: &'a Lexicon<'a>,
+    weight: f64,
+    max_weight: Option<f64>,
 }
 
 /// Enumerator of name candidates that are acronyms of an input text.
+///
+/// Several lexicons can be searched at once with [`Enumerator::add_lexicon`].
+/// If the same word is found in several lexicons, only the match with the highest score is kept,
+/// preferring the lexicon added first on ties.
 pub struct Enumerator<'a> {
-    lex: &'a Lexicon,
-    text: &'a [u8],
-    scores: Vec<usize>,
+    sources: Vec<Source<'a>>,
+    text: Cow<'a, str>,
+    segments: Vec<Range<usize>>,
+    annotations: Vec<Annotation>,
+    // Whether each word is in the given stopwords, and whether it is ignored as a stopword.
+    listed_stopwords: Vec<bool>,
+    stopwords: Vec<bool>,
+    // The words except for the stopwords, given to the scorer, and their indices.
+    content_segments: Vec<Range<usize>>,
+    content_words: Vec<usize>,
+    // The letters to search in the lexicon for the character starting at each position.
+    letters: Vec<Vec<String>>,
+    accent_insensitive: bool,
+    // Whether the character at each position must be matched, i.e., UPPERCASE out of optional words.
+    mandatory: Vec<bool>,
+    scorer: Box<dyn Scorer + 'a>,
+    scores: Vec<u64>,
+    rest_scores: Vec<u64>,
+    // The number of letters from each position to the end.
+    rest_lens: Vec<usize>,
     prefix_len: usize,
+    suffix_len: usize,
+    max_insertions: usize,
+    insertion_penalty: f64,
+    anagram: bool,
+    max_matches: usize,
+    limit_policy: LimitPolicy,
+    weight_ratio: f64,
+    pronounceability: Option<&'a Pronounceability>,
+    pronounceability_ratio: f64,
+    min_pronounceability: f64,
+    denylist: Option<&'a Denylist>,
+    registry: Option<&'a Registry>,
+    hide_taken: bool,
+    max_distance: usize,
+    max_conflicts: usize,
+    // The words given by Constraint::StartsWith, Constraint::UseWord (or `!`), and Constraint::UseEveryWord.
+    start_word: Option<usize>,
+    required_words: Vec<bool>,
+    use_every_word: bool,
+    // The position of the letter to start with, resolved from `start_word`.
+    start_pos: Option<usize>,
+    // The start of the word required to be used, at the position of its last letter.
+    required_starts: Vec<Option<usize>>,
+    min_len: usize,
+    max_len: usize,
+    display_forms: bool,
 }
 
 impl<'a> Enumerator<'a> {
     /// Creates an instance.
-    pub fn new(lex: &'a Lexicon, text: &'a str) -> Result<Self> {
-        let text = text.as_bytes();
-        if 128 <= text.len() {
-            return Err(anyhow!(
-                "the length of an input text must be less than 128."
-            ));
-        }
-        let scores = Self::build_scores(text);
-        let enumerator = Self {
-            lex,
+    ///
+    /// The text is normalized into NFC, and its letters are matched with the words
+    /// after case folding (e.g., `É` matches `é`).
+    /// The text is split into words with the default [`Tokenizer`].
+    ///
+    /// Each word can be followed by markup without spaces:
+    ///
+    /// - `^N` multiplies the scores of its letters by `N` in `1..=16` (e.g., `character^2`),
+    /// - `!` requires at least one letter to be taken from it, as [`Constraint::UseWord`] (e.g., `double!`), and
+    /// - `?` makes it optional, i.e., its UPPERCASE letters can be skipped and
+    ///   [`Constraint::UseEveryWord`] does not apply to it (e.g., `Array?`).
+    ///
+    /// The markup is printed as it is in the descriptions of [`Enumerator::format_match`],
+    /// except in the anagram mode, where only the words are printed.
+    pub fn new(lex: &'a Lexicon<'a>, text: &'a str) -> Result<Self> {
+        Self::with_tokenizer(lex, text, Tokenizer::default())
+    }
+
+    /// Creates an instance, splitting the text into words with the tokenizer.
+    ///
+    /// Only the letters in the words are matched, and the other characters
+    /// such as hyphens and punctuation are skipped.
+    pub fn with_tokenizer(
+        lex: &'a Lexicon<'a>,
+        text: &'a str,
+        tokenizer: Tokenizer,
+    ) -> Result<Self> {
+        let text = if is_nfc(text) {
+            Cow::Borrowed(text)
+        } else {
+            Cow::Owned(text.nfc().collect())
+        };
+        let segments = tokenizer.tokenize(&text);
+        let annotations = segments
+            .iter()
+            .map(|seg| Annotation::parse(&text[seg.end..]))
+            .collect::<Result<Vec<_>>>()?;
+        let letters = Self::build_letters(&text, &segments, false);
+        let mandatory = Self::build_mandatory(&text, &segments, &annotations);
+        let rest_lens = Self::build_rest_lens(&text, &segments);
+        let required_words = annotations.iter().map(|a| a.required).collect();
+        let stopwords = vec![false; segments.len()];
+        let content_segments = segments.clone();
+        let content_words = (0..segments.len()).collect();
+        let mut enumerator = Self {
+            sources: vec![Source {
+                lex,
+                weight: 1.0,
+                max_weight: lex.max_weight(),
+            }],
             text,
-            scores,
+            segments,
+            annotations,
+            listed_stopwords: stopwords.clone(),
+            stopwords,
+            content_segments,
+            content_words,
+            letters,
+            accent_insensitive: false,
+            mandatory,
+            scorer: Box::new(PositionalScorer),
+            scores: vec![],
+            rest_scores: vec![],
+            rest_lens,
             prefix_len: 0,
+            suffix_len: 0,
+            max_insertions: 0,
+            insertion_penalty: 0.0,
+            anagram: false,
+            max_matches: MAX_MATCHES,
+            limit_policy: LimitPolicy::default(),
+            weight_ratio: 0.0,
+            pronounceability: None,
+            pronounceability_ratio: 0.0,
+            min_pronounceability: 0.0,
+            denylist: None,
+            registry: None,
+            hide_taken: false,
+            max_distance: 0,
+            max_conflicts: 0,
+            start_word: None,
+            required_words,
+            use_every_word: false,
+            start_pos: None,
+            required_starts: vec![],
+            min_len: 0,
+            max_len: usize::MAX,
+            display_forms: false,
         };
+        enumerator.resolve_words()?;
         Ok(enumerator)
     }
 
     /// Specifies the maximum number of arbitrary prefix letters to allow for generating recursive acronyms.
     pub fn prefix_len(mut self, prefix_len: usize) -> Result<Self> {
-        if MAX_PREFIX_LEN < prefix_len {
+        if MAX_AFFIX_LEN < prefix_len {
             return Err(anyhow!(
                 "the prefix length must be no more than {}.",
-                MAX_PREFIX_LEN
+                MAX_AFFIX_LEN
             ));
         }
         self.prefix_len = prefix_len;
         Ok(self)
     }
 
-    /// Generates name candidates.
-    pub fn all_subsequences(&self) -> Result<Vec<Match>> {
-        let mut matched = HashMap::new();
-        self.all_subsequences_recur(
-            State::new(Trie::root_pos(), 0, 0, 0, Prefix::new()),
-            &mut matched,
-        )?;
-        let mut matched: Vec<_> = matched.iter().map(|(_, m)| m.clone()).collect();
-        matched.sort_by(|m1, m2| {
-            m2.score
-                .cmp(&m1.score)
-                .then_with(|| m1.word_id.cmp(&m2.word_id))
+    /// Specifies the maximum number of arbitrary suffix letters to append to acronyms
+    /// (e.g., `-er` and `-ly`).
+    pub fn suffix_len(mut self, suffix_len: usize) -> Result<Self> {
+        if MAX_AFFIX_LEN < suffix_len {
+            return Err(anyhow!(
+                "the suffix length must be no more than {}.",
+                MAX_AFFIX_LEN
+            ));
+        }
+        self.suffix_len = suffix_len;
+        Ok(self)
+    }
+
+    /// Specifies the scoring scheme (default: [`PositionalScorer`]).
+    pub fn scorer<S>(mut self, scorer: S) -> Result<Self>
+    where
+        S: Scorer + 'a,
+    {
+        self.scorer = Box::new(scorer);
+        self.update_scores()?;
+        Ok(self)
+    }
+
+    /// Specifies the maximum number of arbitrary letters to insert between the letters of acronyms
+    /// (e.g., a vowel between two consonants), and the penalty of each inserted letter (default: 0).
+    ///
+    /// The score of each candidate is multiplied by `(1 - penalty)^n`,
+    /// where `n` is the number of inserted letters.
+    pub fn insertions(mut self, max_insertions: usize, penalty: f64) -> Result<Self> {
+        if !(0.0..=1.0).contains(&penalty) {
+            return Err(anyhow!("the insertion penalty must be in [0, 1]."));
+        }
+        self.max_insertions = max_insertions;
+        self.insertion_penalty = penalty;
+        Ok(self)
+    }
+
+    /// Specifies whether to permute the words in the text (default: `false`).
+    ///
+    /// If enabled, the text is treated as a bag of words, and the letters of each word are taken
+    /// in order after those of the preceding words in every permutation
+    /// (e.g., `array double dictionary character` for `character wise double array dictionary`).
+    /// The number of words must be no more than 8, excluding the stopwords that are left out;
+    /// otherwise, the search fails with an error.
+    pub const fn anagram(mut self, yes: bool) -> Self {
+        self.anagram = yes;
+        self
+    }
+
+    /// Specifies whether to match letters ignoring their diacritics (default: `false`).
+    ///
+    /// If enabled, e.g., `e` in the text matches `é` and `è` in words, and vice versa.
+    pub fn accent_insensitive(mut self, yes: bool) -> Self {
+        self.accent_insensitive = yes;
+        self.ignore_stopwords();
+        self
+    }
+
+    /// Specifies the words to ignore in the text, such as [`Stopwords::english`].
+    ///
+    /// The stopwords in the text are skipped without contributing letters,
+    /// and excluded from the words given to the scorer, e.g., in computing the maximum length.
+    /// The stopwords containing UPPERCASE letters (except for those starting camelCase words),
+    /// followed by markup, or given in [`Constraint::StartsWith`] or [`Constraint::UseWord`]
+    /// are not ignored, since they are intended to be used.
+    pub fn stopwords(mut self, stopwords: &Stopwords) -> Result<Self> {
+        self.listed_stopwords = self
+            .segments
+            .iter()
+            .map(|seg| stopwords.contains(&self.text[seg.clone()]))
+            .collect();
+        self.resolve_words()?;
+        Ok(self)
+    }
+
+    /// Adds a lexicon to search, whose candidates' scores are multiplied by `weight`.
+    pub fn add_lexicon(mut self, lex: &'a Lexicon<'a>, weight: f64) -> Result<Self> {
+        if !weight.is_finite() || weight <= 0.0 {
+            return Err(anyhow!("the lexicon weight must be finite and positive."));
+        }
+        let max_weight = lex.max_weight();
+        if self.weight_ratio != 0.0 && max_weight.is_none() {
+            return Err(anyhow!("the lexicon must be given weights."));
+        }
+        self.sources.push(Source {
+            lex,
+            weight,
+            max_weight,
         });
-        Ok(matched)
-    }
-
-    fn build_scores(text: &'a [u8]) -> Vec<usize> {
-        let mut scores = vec![0; text.len()];
-        let max_word_len = text
-            .split(|&c| c == DELIMITER)
-            .fold(0, |max, sub| max.max(sub.len()));
-        let max_score = 1 << (max_word_len - 1);
-        let mut curr_score = 0;
-        for (&c, score) in text.iter().zip(scores.iter_mut()) {
-            if c == DELIMITER {
-                curr_score = 0;
-            } else if curr_score == 0 {
-                curr_score = max_score;
-            } else {
-                curr_score /= 2;
-            }
-            *score = curr_score;
+        Ok(self)
+    }
+
+    /// Specifies the ratio to combine the word weights given in [`Lexicon::with_weights`]
+    /// with the acronym scores (default: 0).
+    ///
+    /// The score of each candidate is multiplied by `1 + weight_ratio * w`,
+    /// where `w` is the weight of the word normalized by the maximum one in the lexicon.
+    pub fn weight_ratio(mut self, weight_ratio: f64) -> Result<Self> {
+        if !weight_ratio.is_finite() || weight_ratio < 0.0 {
+            return Err(anyhow!("the weight ratio must be finite and non-negative."));
+        }
+        if self.sources.iter().any(|src| src.max_weight.is_none()) {
+            return Err(anyhow!("the lexicon must be given weights."));
         }
-        scores
+        self.weight_ratio = weight_ratio;
+        Ok(self)
     }
 
-    fn all_subsequences_recur(
-        &self,
-        state: State,
-        matched: &mut HashMap<usize, Match>,
-    ) -> Result<()> {
-        let State {
-            node_pos,
-            text_pos,
-            score,
-            positions,
-            prefix,
-        } = state;
+    /// Specifies the model to estimate the pronounceability of each candidate.
+    pub const fn pronounceability(mut self, model: &'a Pronounceability) -> Self {
+        self.pronounceability = Some(model);
+        self
+    }
 
-        if text_pos == 0 && prefix.len() < self.prefix_len {
-            for c in b'a'..=b'z' {
-                if let Some(child_pos) = self.lex.trie().get_child(node_pos, c) {
-                    // Because score is not incremented, the score of a recursive acronym never become
-                    // larger than that of the equivalent acronym.
-                    self.all_subsequences_recur(
-                        State::new(child_pos, text_pos, score, positions, prefix.push(c)),
-                        matched,
-                    )?;
+    /// Specifies the ratio to combine the pronounceability with the scores (default: 0).
+    ///
+    /// The score of each candidate is multiplied by `1 + pronounceability_ratio * p`,
+    /// where `p` is the pronounceability of the word.
+    pub fn pronounceability_ratio(mut self, pronounceability_ratio: f64) -> Result<Self> {
+        if !pronounceability_ratio.is_finite() || pronounceability_ratio < 0.0 {
+            return Err(anyhow!(
+                "the pronounceability ratio must be finite and non-negative."
+            ));
+        }
+        if self.pronounceability.is_none() {
+            return Err(anyhow!("the pronounceability model must be given."));
+        }
+        self.pronounceability_ratio = pronounceability_ratio;
+        Ok(self)
+    }
+
+    /// Specifies the minimum pronounceability of candidates to generate in [0, 1] (default: 0).
+    pub fn min_pronounceability(mut self, min_pronounceability: f64) -> Result<Self> {
+        if !(0.0..=1.0).contains(&min_pronounceability) {
+            return Err(anyhow!("the minimum pronounceability must be in [0, 1]."));
+        }
+        if self.pronounceability.is_none() {
+            return Err(anyhow!("the pronounceability model must be given."));
+        }
+        self.min_pronounceability = min_pronounceability;
+        Ok(self)
+    }
+
+    /// Specifies the words never to generate as candidates.
+    pub const fn denylist(mut self, denylist: &'a Denylist) -> Self {
+        self.denylist = Some(denylist);
+        self
+    }
+
+    /// Specifies the registry of taken names to check the availability of each candidate.
+    pub const fn registry(mut self, registry: &'a Registry) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    /// Specifies whether to exclude the candidates taken in the registry (default: `false`).
+    pub fn hide_taken(mut self, yes: bool) -> Result<Self> {
+        if self.registry.is_none() {
+            return Err(anyhow!("the registry must be given."));
+        }
+        self.hide_taken = yes;
+        Ok(self)
+    }
+
+    /// Specifies to find at most `max_conflicts` names in the registry
+    /// within Levenshtein distance `max_distance` from each candidate (default: none).
+    ///
+    /// The names are looked up only for the final candidates after deduplication,
+    /// so they are not found in [`Enumerator::subsequences`].
+    pub fn conflicts(mut self, max_distance: usize, max_conflicts: usize) -> Result<Self> {
+        if self.registry.is_none() {
+            return Err(anyhow!("the registry must be given."));
+        }
+        self.max_distance = max_distance;
+        self.max_conflicts = max_conflicts;
+        Ok(self)
+    }
+
+    /// Adds a constraint on candidates.
+    pub fn constraint(mut self, constraint: Constraint) -> Result<Self> {
+        match constraint {
+            Constraint::StartsWith(i) => {
+                self.check_word(i)?;
+                if self.start_word.is_some_and(|j| j != i) {
+                    return Err(anyhow!("the word to start with must be unique."));
                 }
+                self.start_word = Some(i);
+                self.resolve_words()?;
+            }
+            Constraint::UseWord(i) => {
+                self.check_word(i)?;
+                self.required_words[i] = true;
+                self.resolve_words()?;
+            }
+            Constraint::UseEveryWord => {
+                self.use_every_word = true;
+                self.resolve_words()?;
+            }
+            Constraint::MinLength(min_len) => {
+                if self.max_len < min_len {
+                    return Err(anyhow!(
+                        "the minimum length must be no more than the maximum."
+                    ));
+                }
+                self.min_len = min_len;
+            }
+            Constraint::MaxLength(max_len) => {
+                if max_len < self.min_len {
+                    return Err(anyhow!(
+                        "the minimum length must be no more than the maximum."
+                    ));
+                }
+                self.max_len = max_len;
             }
         }
+        Ok(self)
+    }
 
-        if text_pos == self.text.len() {
-            if let Some(word_id) = self.lex.trie().get_value(node_pos) {
-                matched
-                    .entry(word_id)
-                    .and_modify(|m| {
-                        debug_assert_eq!(m.word_id, word_id);
-                        if m.score < score {
-                            m.score = score;
-                            m.positions = positions;
-                            m.prefix = prefix.string();
-                        }
-                    })
-                    .or_insert(Match {
-                        word_id,
-                        score,
-                        positions,
-                        prefix: prefix.string(),
-                    });
-                if MAX_MATCHES <= matched.len() {
+    /// Specifies the minimum number of letters of candidates, including the prefix and suffix (default: 0).
+    ///
+    /// The states that cannot reach the length with the rest of the text are not expanded.
+    /// This is a shorthand of [`Constraint::MinLength`].
+    pub fn min_len(self, min_len: usize) -> Result<Self> {
+        self.constraint(Constraint::MinLength(min_len))
+    }
+
+    /// Specifies the maximum number of letters of candidates, including the prefix and suffix
+    /// (default: unlimited).
+    ///
+    /// The trie is not descended beyond the length, which also reduces the number of candidates.
+    /// This is a shorthand of [`Constraint::MaxLength`].
+    pub fn max_len(self, max_len: usize) -> Result<Self> {
+        self.constraint(Constraint::MaxLength(max_len))
+    }
+
+    /// Specifies the maximum number of candidates to generate in [`Enumerator::all_subsequences`]
+    /// (default: 10000).
+    pub fn max_matches(mut self, max_matches: usize) -> Result<Self> {
+        if max_matches == 0 {
+            return Err(anyhow!("the maximum number of matches must be positive."));
+        }
+        self.max_matches = max_matches;
+        Ok(self)
+    }
+
+    /// Specifies what to do when the number of candidates exceeds the maximum
+    /// (default: [`LimitPolicy::Error`]).
+    pub const fn limit_policy(mut self, limit_policy: LimitPolicy) -> Self {
+        self.limit_policy = limit_policy;
+        self
+    }
+
+    /// Specifies whether [`Enumerator::format_match`] renders the display forms of words
+    /// given in [`Lexicon::display_forms`] (default: `false`).
+    pub const fn display_forms(mut self, yes: bool) -> Self {
+        self.display_forms = yes;
+        self
+    }
+
+    /// Generates name candidates in score order.
+    ///
+    /// If the number of candidates exceeds the maximum specified with [`Enumerator::max_matches`],
+    /// the behavior follows [`Enumerator::limit_policy`].
+    pub fn all_subsequences(&self) -> Result<Matches> {
+        self.check_anagram()?;
+        if self.limit_policy == LimitPolicy::KeepBest {
+            let (mut matches, complete) = self.best_matches(self.max_matches);
+            self.find_conflicts(&mut matches);
+            return Ok(Matches { matches, complete });
+        }
+        let mut matched: HashMap<&str, Match> = HashMap::new();
+        let mut complete = true;
+        for m in self.search() {
+            if let Some(e) = matched.get_mut(self.word(&m)) {
+                if e.score < m.score {
+                    *e = m;
+                }
+                continue;
+            }
+            if self.max_matches <= matched.len() {
+                if self.limit_policy == LimitPolicy::Error {
                     return Err(anyhow!(
                         "#matches is too many, exceeding {}. Edit your input to reduce the number by shortening the description, specifying more UPPERCASE letters, etc.",
-                        MAX_MATCHES
+                        self.max_matches
                     ));
                 }
+                complete = false;
+                break;
             }
-            return Ok(());
+            matched.insert(self.word(&m), m);
         }
+        let mut matches: Vec<_> = matched.into_values().collect();
+        matches.sort_by_key(|m| (Reverse(m.score), m.lexicon_id, m.word_id));
+        self.find_conflicts(&mut matches);
+        Ok(Matches { matches, complete })
+    }
 
-        let c = self.text[text_pos];
-        if !utils::is_upper_case(c) {
-            // Allows an epsilon transition only for non upper letters.
-            self.all_subsequences_recur(
-                State::new(node_pos, text_pos + 1, score, positions, prefix),
-                matched,
-            )?;
+    /// Generates the top-k name candidates.
+    ///
+    /// The result is the same as the first `k` candidates of [`Enumerator::all_subsequences`],
+    /// but this never fails because of too many matches. The search is sped up by pruning
+    /// the states whose best possible score cannot reach the current k-th best one.
+    pub fn top_k(&self, k: usize) -> Result<Vec<Match>> {
+        self.check_anagram()?;
+        let mut matches = self.best_matches(k).0;
+        self.find_conflicts(&mut matches);
+        Ok(matches)
+    }
+
+    /// Generates the top-k name candidates,
+    /// also returning whether no other candidates can exist.
+    fn best_matches(&self, k: usize) -> (Vec<Match>, bool) {
+        if k == 0 {
+            return (vec![], self.search().next().is_none());
+        }
+        // Ranks of the current top-k candidates, in which the last one is the k-th best.
+        let mut ranked = BTreeSet::new();
+        let mut matched: HashMap<&str, Match> = HashMap::new();
+        let mut complete = true;
+        let mut iter = self.search();
+        loop {
+            let min_score = if ranked.len() == k {
+                let &(Reverse(score), _, _) = ranked.last().unwrap();
+                score
+            } else {
+                0
+            };
+            let m = match iter.next_with_min_score(min_score) {
+                Some(m) => m,
+                None => break,
+            };
+            if let Some(e) = matched.get_mut(self.word(&m)) {
+                if e.score < m.score {
+                    ranked.remove(&(Reverse(e.score), e.lexicon_id, e.word_id));
+                    ranked.insert((Reverse(m.score), m.lexicon_id, m.word_id));
+                    *e = m;
+                }
+                continue;
+            }
+            let rank = (Reverse(m.score), m.lexicon_id, m.word_id);
+            if ranked.len() == k {
+                complete = false;
+                if ranked.last().unwrap() < &rank {
+                    continue;
+                }
+                let (_, lexicon_id, word_id) = ranked.pop_last().unwrap();
+                matched.remove(self.sources[lexicon_id].lex.word(word_id));
+            }
+            ranked.insert(rank);
+            matched.insert(self.word(&m), m);
         }
+        let matches = ranked
+            .into_iter()
+            .map(|(_, lexicon_id, word_id)| {
+                matched
+                    .remove(self.sources[lexicon_id].lex.word(word_id))
+                    .unwrap()
+            })
+            .collect();
+        (matches, complete && !iter.pruned)
+    }
+
+    /// Creates an iterator to lazily generate name candidates.
+    ///
+    /// Unlike [`Enumerator::all_subsequences`], the candidates are neither deduplicated nor sorted,
+    /// that is, the same word can be yielded several times with different positions.
+    /// The number of candidates is not limited, so you can stop the iteration anytime you want.
+    pub fn subsequences(&self) -> Result<Subsequences<'_>> {
+        self.check_anagram()?;
+        Ok(self.search())
+    }
 
-        let c = utils::to_lower_case(c).unwrap_or(c);
-        if let Some(child_pos) = self.lex.trie().get_child(node_pos, c) {
-            self.all_subsequences_recur(
-                State::new(
-                    child_pos,
-                    text_pos + 1,
-                    score + self.scores[text_pos],
-                    positions | (1 << text_pos),
-                    prefix,
-                ),
-                matched,
-            )?;
+    /// Creates an iterator to lazily generate name candidates, whose settings have been checked.
+    fn search(&self) -> Subsequences<'_> {
+        Subsequences {
+            enumerator: self,
+            lexicon_id: 0,
+            stack: vec![State::root()],
+            path: Path::default(),
+            pruned: false,
         }
-        Ok(())
     }
 
-    /// Returns the formatted name candidate and description.
-    pub fn format_match(&self, m: &Match) -> (String, String) {
-        let word = {
-            let word = self.lex.word(m.word_id);
-            assert!(word.starts_with(&m.prefix));
-            let mut bytes = word.as_bytes().to_vec();
-            for c in bytes[..m.prefix.len()].iter_mut() {
-                *c = utils::to_upper_case(*c).unwrap();
-            }
-            String::from_utf8(bytes).unwrap()
+    /// Finds the registered names close to the final candidates, if requested.
+    fn find_conflicts(&self, matches: &mut [Match]) {
+        let registry = match self.registry {
+            Some(registry) if self.max_conflicts != 0 => registry,
+            _ => return,
         };
-        let desc = {
-            let mut bytes = self.text.to_vec();
-            for (i, c) in bytes.iter_mut().enumerate() {
-                if m.positions & (1 << i) != 0 {
-                    *c = utils::to_upper_case(*c).unwrap_or(*c);
-                } else {
-                    assert!(!utils::is_upper_case(*c));
-                }
-            }
-            String::from_utf8(bytes).unwrap()
-        };
-        (word, desc)
+        for m in matches {
+            m.conflicts = registry.nearest(self.word(m), self.max_distance, self.max_conflicts);
+        }
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    #[test]
-    fn test_enumerate() {
-        let words = &["aa", "abaab", "abb", "bab", "bb", "bbb", "cbab", "ccbab"];
-        let lex = Lexicon::new(words).unwrap();
-        let text = "abAaB";
+    /// Gets the word of the match.
+    fn word(&self, m: &Match) -> &str {
+        self.sources[m.lexicon_id].lex.word(m.word_id)
+    }
 
-        let enumerator = Enumerator::new(&lex, text).unwrap();
-        let matched = enumerator.all_subsequences().unwrap();
-        let expected = vec![
-            Match {
-                word_id: 1,
-                score: 31,
-                positions: 0b11111,
-                prefix: "".to_string(),
-            }, // "abAaB"
-            Match {
-                word_id: 3,
-                score: 13,
-                positions: 0b10110,
-                prefix: "".to_string(),
-            }, // "bAB"
-        ];
-        assert_eq!(&matched, &expected);
+    /// Checks if the i-th word exists.
+    fn check_word(&self, i: usize) -> Result<()> {
+        if self.segments.len() <= i {
+            return Err(anyhow!(
+                "the word index must be less than the number of words, {}.",
+                self.segments.len()
+            ));
+        }
+        Ok(())
+    }
 
-        assert_eq!(
-            enumerator.format_match(&matched[0]),
-            ("abaab".to_string(), "ABAAB".to_string())
-        );
-        assert_eq!(
-            enumerator.format_match(&matched[1]),
-            ("bab".to_string(), "aBAaB".to_string())
-        );
+    /// Checks if the number of words to permute is small enough in the anagram mode.
+    fn check_anagram(&self) -> Result<()> {
+        if self.anagram && MAX_ANAGRAM_WORDS < self.content_words.len() {
+            return Err(anyhow!(
+                "the number of words must be no more than {} in the anagram mode.",
+                MAX_ANAGRAM_WORDS
+            ));
+        }
+        Ok(())
     }
 
-    #[test]
-    fn test_enumerate_1() {
-        let words = &["aa", "abaab", "abb", "bab", "bb", "bbb", "cbab", "ccbab"];
-        let lex = Lexicon::new(words).unwrap();
-        let text = "abAaB";
+    /// Resolves the stopwords and the words used in the constraints, which can be given in any order.
+    fn resolve_words(&mut self) -> Result<()> {
+        self.stopwords = (0..self.segments.len())
+            .map(|i| {
+                self.listed_stopwords[i]
+                    && !self.mandatory[self.segments[i].clone()].contains(&true)
+                    && self.annotations[i] == Annotation::default()
+                    && !self.required_words[i]
+                    && self.start_word != Some(i)
+            })
+            .collect();
+        self.content_words = (0..self.segments.len())
+            .filter(|&i| !self.stopwords[i])
+            .collect();
+        self.content_segments = self
+            .content_words
+            .iter()
+            .map(|&i| self.segments[i].clone())
+            .collect();
+        self.update_scores()?;
+        self.ignore_stopwords();
 
-        let enumerator = Enumerator::new(&lex, text).unwrap().prefix_len(1).unwrap();
-        let matched = enumerator.all_subsequences().unwrap();
-        let expected = vec![
-            Match {
-                word_id: 1,
-                score: 31,
-                positions: 0b11111,
-                prefix: "".to_string(),
-            }, // "abAaB"
-            Match {
-                word_id: 3,
-                score: 13,
-                positions: 0b10110,
-                prefix: "".to_string(),
-            }, // "bAB"
-            Match {
-                word_id: 6,
-                score: 13,
-                positions: 0b10110,
-                prefix: "c".to_string(),
-            }, // "c|bAB"
-        ];
-        assert_eq!(matched, expected);
+        self.start_pos = self.start_word.map(|i| self.segments[i].start);
+        self.required_starts = vec![None; self.text.len()];
+        for i in 0..self.segments.len() {
+            let every = self.use_every_word && !self.stopwords[i] && !self.annotations[i].optional;
+            if self.required_words[i] || every {
+                self.require_segment(self.segments[i].clone());
+            }
+        }
+        Ok(())
+    }
 
-        assert_eq!(
-            enumerator.format_match(&matched[0]),
-            ("abaab".to_string(), "ABAAB".to_string())
-        );
-        assert_eq!(
-            enumerator.format_match(&matched[1]),
-            ("bab".to_string(), "aBAaB".to_string())
-        );
-        assert_eq!(
-            enumerator.format_match(&matched[2]),
-            ("Cbab".to_string(), "aBAaB".to_string())
-        );
+    /// Computes the position scores of the words except for the stopwords, boosted by the markup.
+    fn update_scores(&mut self) -> Result<()> {
+        let mut scores = self
+            .scorer
+            .position_scores(&self.text, &self.content_segments)?;
+        for (seg, annotation) in self.segments.iter().zip(&self.annotations) {
+            scores[seg.clone()]
+                .iter_mut()
+                .for_each(|score| *score *= annotation.boost);
+        }
+        self.rest_scores = Self::build_rest_scores(&scores)?;
+        self.scores = scores;
+        Ok(())
+    }
+
+    /// Rebuilds the letters, clearing those of the stopwords so that they are never matched.
+    fn ignore_stopwords(&mut self) {
+        self.letters = Self::build_letters(&self.text, &self.segments, self.accent_insensitive);
+        for (seg, &stopword) in self.segments.iter().zip(&self.stopwords) {
+            if stopword {
+                self.letters[seg.clone()].iter_mut().for_each(Vec::clear);
+            }
+        }
+    }
+
+    /// Gets the set of the words to permute in the anagram mode, i.e., the ones except for stopwords.
+    const fn anagram_words(&self) -> u64 {
+        (1 << self.content_words.len()) - 1
+    }
+
+    /// Requires a letter from the word, which is checked when leaving its last letter.
+    fn require_segment(&mut self, segment: Range<usize>) {
+        let c = self.text[..segment.end].chars().next_back().unwrap();
+        self.required_starts[segment.end - c.len_utf8()] = Some(segment.start);
+    }
+
+    /// Lists the letters to search for the character starting at each position,
+    /// leaving those empty out of the words.
+    fn build_letters(
+        text: &str,
+        segments: &[Range<usize>],
+        accent_insensitive: bool,
+    ) -> Vec<Vec<String>> {
+        let mut letters = vec![vec![]; text.len()];
+        for (i, c) in segments.iter().flat_map(|seg| {
+            text[seg.clone()]
+                .char_indices()
+                .map(|(i, c)| (seg.start + i, c))
+        }) {
+            let lower = utils::to_lower_case(c);
+            let mut chars = lower.chars();
+            let single = chars.next().filter(|_| chars.next().is_none());
+            letters[i].push(lower);
+            if let Some(l) = single.filter(|_| accent_insensitive) {
+                let base = utils::base_letter(l);
+                for a in std::iter::once(&base).chain(utils::accented_letters(base)) {
+                    let a = a.to_string();
+                    if !letters[i].contains(&a) {
+                        letters[i].push(a);
+                    }
+                }
+            }
+        }
+        letters
+    }
+
+    /// Checks if the character at each position must be matched.
+    ///
+    /// The UPPERCASE letters starting the words split at camelCase boundaries,
+    /// i.e., adjacent to the preceding words, are not mandatory.
+    fn build_mandatory(
+        text: &str,
+        segments: &[Range<usize>],
+        annotations: &[Annotation],
+    ) -> Vec<bool> {
+        let mut mandatory = vec![false; text.len()];
+        for (i, (seg, annotation)) in segments.iter().zip(annotations).enumerate() {
+            if annotation.optional {
+                continue;
+            }
+            for (j, c) in text[seg.clone()].char_indices() {
+                mandatory[seg.start + j] = utils::is_upper_case(c);
+            }
+            if i != 0 && segments[i - 1].end == seg.start {
+                mandatory[seg.start] = false;
+            }
+        }
+        mandatory
+    }
+
+    /// Counts the letters in the words from each position to the end.
+    fn build_rest_lens(text: &str, segments: &[Range<usize>]) -> Vec<usize> {
+        let mut rest_lens = vec![0; text.len() + 1];
+        let mut in_word = vec![false; text.len()];
+        for seg in segments {
+            in_word[seg.clone()].fill(true);
+        }
+        for (i, c) in text.char_indices().rev() {
+            rest_lens[i] = rest_lens[i + c.len_utf8()] + usize::from(in_word[i]);
+        }
+        rest_lens
+    }
+
+    /// Computes the maximum score that can be added from each position to the end.
+    fn build_rest_scores(scores: &[u64]) -> Result<Vec<u64>> {
+        let mut rest_scores = vec![0u64; scores.len() + 1];
+        for (i, &score) in scores.iter().enumerate().rev() {
+            rest_scores[i] = rest_scores[i + 1]
+                .checked_add(score)
+                .ok_or_else(|| anyhow!("the text has too many words to be scored."))?;
+        }
+        Ok(rest_scores)
+    }
+
+    /// Combines the score of a candidate with the weights of its lexicon and word,
+    /// the pronounceability of its word, and the penalty of its inserted letters.
+    fn weighted_score(&self, m: &Match) -> u64 {
+        let src = &self.sources[m.lexicon_id];
+        let mut factor = src.weight;
+        let max_weight = src.max_weight.unwrap_or(0.0);
+        if self.weight_ratio != 0.0 && max_weight != 0.0 {
+            let weight = src.lex.weight(m.word_id).unwrap() / max_weight;
+            factor *= self.weight_ratio.mul_add(weight, 1.0);
+        }
+        if let Some(p) = m.pronounceability {
+            factor *= self.pronounceability_ratio.mul_add(p, 1.0);
+        }
+        factor *= self.insertion_penalty(m.insertions.len());
+        (m.score as f64 * factor) as u64
+    }
+
+    /// Sums the values from the state to the end, such as the scores and lengths,
+    /// where `rest` has the sums from each position to the end of the text.
+    fn rest_sum<T>(&self, state: &State, rest: &[T]) -> T
+    where
+        T: Copy + Default + AddAssign + Sub<Output = T>,
+    {
+        if !self.anagram {
+            return rest[state.text_pos];
+        }
+        let mut sum = T::default();
+        for (i, seg) in self.content_segments.iter().enumerate() {
+            if state.visited & (1 << i) == 0 {
+                sum += rest[seg.start] - rest[seg.end];
+            } else if i == state.segment {
+                sum += rest[state.text_pos] - rest[seg.end];
+            }
+        }
+        sum
+    }
+
+    /// Computes the factor of the penalty of the inserted letters.
+    fn insertion_penalty(&self, num_insertions: usize) -> f64 {
+        (1.0 - self.insertion_penalty).powi(num_insertions as i32)
+    }
+
+    /// Returns the upper bound of the scores of candidates in the lexicon
+    /// whose sums of the position scores are no more than `score`
+    /// and which have at least `num_insertions` inserted letters.
+    fn max_score(&self, lexicon_id: usize, score: u64, num_insertions: usize) -> u64 {
+        let score = self.scorer.max_candidate_score(score);
+        let weight = self.sources[lexicon_id].weight;
+        if self.weight_ratio == 0.0
+            && self.pronounceability_ratio == 0.0
+            && weight == 1.0
+            && num_insertions == 0
+        {
+            return score;
+        }
+        let factor = (1.0 + self.weight_ratio)
+            * (1.0 + self.pronounceability_ratio)
+            * weight
+            * self.insertion_penalty(num_insertions);
+        (score as f64 * factor).ceil() as u64
+    }
+
+    /// Pushes the states following the given one into the stack,
+    /// and returns the match if the state reaches the end of the text.
+    ///
+    /// The states are pushed in the reverse order so that they are popped in the depth-first order.
+    /// Since every state is popped after its ancestors and before the states not in its subtree,
+    /// `path` can be shared to keep the path to the given state.
+    ///
+    /// In the anagram mode, the states at the end of a word jump to the beginnings of
+    /// the words not visited yet.
+    fn expand(
+        &self,
+        lexicon_id: usize,
+        state: State,
+        stack: &mut Vec<State>,
+        path: &mut Path,
+    ) -> Option<Match> {
+        let State {
+            node_pos,
+            text_pos,
+            score,
+            num_positions,
+            consumed,
+            prefix,
+            suffix,
+            depth,
+            num_insertions,
+            inserted,
+            segment,
+            visited,
+        } = state;
+        let Path {
+            positions,
+            insertions,
+            order,
+        } = path;
+
+        if consumed {
+            let c = self.text[..text_pos].chars().next_back().unwrap();
+            positions.truncate(num_positions - 1);
+            positions.push(text_pos - c.len_utf8());
+        } else {
+            positions.truncate(num_positions);
+        }
+        debug_assert_eq!(positions.len(), num_positions);
+
+        if inserted {
+            insertions.truncate(num_insertions - 1);
+            insertions.push(depth - 1);
+        } else {
+            insertions.truncate(num_insertions);
+        }
+        debug_assert_eq!(insertions.len(), num_insertions);
+
+        if visited != 0 {
+            order.truncate(visited.count_ones() as usize - 1);
+            order.push(self.content_words[segment]);
+        }
+
+        let (at_beginning, at_word_end, at_end) = if self.anagram {
+            let at_word_end = visited == 0 || text_pos == self.content_segments[segment].end;
+            let all_visited = visited == self.anagram_words();
+            (visited == 0, at_word_end, at_word_end && all_visited)
+        } else {
+            (text_pos == 0, false, text_pos == self.text.len())
+        };
+
+        let lex = self.sources[lexicon_id].lex;
+        let len = prefix.len() + num_positions + suffix.len() + num_insertions;
+
+        // The prefix letters can be added only at the beginning.
+        let rest_prefix_len = if at_beginning {
+            self.prefix_len - prefix.len()
+        } else {
+            0
+        };
+        let rest_suffix_len = self.suffix_len - suffix.len();
+        let rest_insertions = self.max_insertions - num_insertions;
+        let rest_len = self.rest_sum(&state, &self.rest_lens);
+        if len + rest_prefix_len + rest_len + rest_suffix_len + rest_insertions < self.min_len {
+            return None;
+        }
+
+        if at_end {
+            // The suffix letters are appended only to the acronym of at least one letter.
+            if suffix.len() < self.suffix_len && num_positions != 0 && len < self.max_len {
+                for c in (b'a'..=b'z').rev() {
+                    if let Some(child_pos) = lex.trie().get_child(node_pos, c) {
+                        stack.push(State {
+                            node_pos: child_pos,
+                            consumed: false,
+                            suffix: suffix.push(c),
+                            depth: depth + 1,
+                            ..state
+                        });
+                    }
+                }
+            }
+            if len < self.min_len {
+                return None;
+            }
+            let word_id = lex.trie().get_value(node_pos)?;
+            let word = lex.word(word_id);
+            if self
+                .denylist
+                .is_some_and(|denylist| denylist.contains(word))
+            {
+                return None;
+            }
+            let availability = self.registry.map(|registry| registry.check(word));
+            if self.hide_taken && availability == Some(Availability::Taken) {
+                return None;
+            }
+            let pronounceability = self.pronounceability.map(|model| model.score(word));
+            if pronounceability.is_some_and(|p| p < self.min_pronounceability) {
+                return None;
+            }
+            let mut positions = positions.clone();
+            if self.anagram {
+                positions.sort_unstable();
+            }
+            let mut m = Match {
+                word_id,
+                lexicon_id,
+                score,
+                positions,
+                prefix: prefix.string(),
+                suffix: suffix.string(),
+                insertions: insertions.clone(),
+                order: if self.anagram { order.clone() } else { vec![] },
+                pronounceability,
+                availability,
+                conflicts: vec![],
+            };
+            m.score = self.scorer.candidate_score(&Candidate {
+                word,
+                text: &self.text,
+                segments: &self.content_segments,
+                matched: &m,
+            });
+            m.score = self.weighted_score(&m);
+            return Some(m);
+        }
+
+        if at_word_end {
+            for (i, seg) in self.content_segments.iter().enumerate().rev() {
+                if visited & (1 << i) == 0 {
+                    stack.push(State {
+                        text_pos: seg.start,
+                        consumed: false,
+                        segment: i,
+                        visited: visited | (1 << i),
+                        ..state
+                    });
+                }
+            }
+        } else {
+            // The letters are inserted only between consumed ones, so that they never overlap
+            // with the prefix and suffix. They are pushed first to be popped after the others,
+            // since they are penalized.
+            if num_positions != 0 && num_insertions < self.max_insertions && len < self.max_len {
+                for c in (b'a'..=b'z').rev() {
+                    if let Some(child_pos) = lex.trie().get_child(node_pos, c) {
+                        stack.push(State {
+                            node_pos: child_pos,
+                            consumed: false,
+                            depth: depth + 1,
+                            num_insertions: num_insertions + 1,
+                            inserted: true,
+                            ..state
+                        });
+                    }
+                }
+            }
+
+            let c = self.text[text_pos..].chars().next().unwrap();
+            let next_pos = text_pos + c.len_utf8();
+            // The position of the letter to start with, if no letters have been taken from the text.
+            let start_pos = self.start_pos.filter(|_| num_positions == 0);
+            let letters = if len < self.max_len && start_pos.is_none_or(|pos| pos == text_pos) {
+                self.letters[text_pos].as_slice()
+            } else {
+                &[]
+            };
+            for letter in letters.iter().rev() {
+                if let Some(child_pos) = lex.trie().get_descendant(node_pos, letter.as_bytes()) {
+                    stack.push(State {
+                        node_pos: child_pos,
+                        text_pos: next_pos,
+                        score: score + self.scores[text_pos],
+                        num_positions: num_positions + 1,
+                        consumed: true,
+                        depth: depth + letter.len(),
+                        inserted: false,
+                        ..state
+                    });
+                }
+            }
+
+            let required = self.required_starts[text_pos].is_some_and(|start| {
+                !positions
+                    .last()
+                    .is_some_and(|pos| (start..text_pos).contains(pos))
+            });
+            if !self.mandatory[text_pos] && !required && start_pos != Some(text_pos) && !inserted {
+                // Allows an epsilon transition only for non upper letters that can be skipped.
+                stack.push(State {
+                    text_pos: next_pos,
+                    consumed: false,
+                    ..state
+                });
+            }
+        }
+
+        if at_beginning && prefix.len() < self.prefix_len && len < self.max_len {
+            for c in (b'a'..=b'z').rev() {
+                if let Some(child_pos) = lex.trie().get_child(node_pos, c) {
+                    // Because score is not incremented, the score of a recursive acronym never become
+                    // larger than that of the equivalent acronym.
+                    stack.push(State {
+                        node_pos: child_pos,
+                        consumed: false,
+                        prefix: prefix.push(c),
+                        depth: depth + 1,
+                        ..state
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns the formatted name candidate and description.
+    ///
+    /// The prefix, suffix, and inserted letters of the candidate are capitalized,
+    /// unless the display form of the word is rendered as is with [`Enumerator::display_forms`].
+    /// In the anagram mode, the words of the description except for the stopwords are rearranged
+    /// in the order of the match and joined with spaces, dropping the markup and the other characters.
+    pub fn format_match(&self, m: &Match) -> (String, String) {
+        let lex = self.sources[m.lexicon_id].lex;
+        let word = if self.display_forms {
+            lex.display(m.word_id).to_string()
+        } else {
+            let word = lex.word(m.word_id);
+            assert!(word.starts_with(&m.prefix) && word.ends_with(&m.suffix));
+            let suffix_pos = word.len() - m.suffix.len();
+            let mut insertions = m.insertions.iter().peekable();
+            word.char_indices()
+                .map(|(i, c)| {
+                    if i < m.prefix.len() || suffix_pos <= i || insertions.next_if_eq(&&i).is_some()
+                    {
+                        c.to_ascii_uppercase()
+                    } else {
+                        c
+                    }
+                })
+                .collect()
+        };
+        let desc = {
+            // The words are rearranged in the anagram mode.
+            let ranges: Vec<_> = if m.order.is_empty() {
+                std::iter::once(0..self.text.len()).collect()
+            } else {
+                m.order.iter().map(|&i| self.segments[i].clone()).collect()
+            };
+            let mut desc = String::with_capacity(self.text.len());
+            for range in ranges {
+                if !desc.is_empty() {
+                    desc.push(char::from(DELIMITER));
+                }
+                for (i, c) in self.text[range.clone()].char_indices() {
+                    if m.positions.binary_search(&(range.start + i)).is_ok() {
+                        desc.push_str(&utils::to_upper_case(c));
+                    } else if utils::is_upper_case(c) {
+                        // The UPPERCASE letters of optional words or starting camelCase words.
+                        assert!(!self.mandatory[range.start + i]);
+                        desc.push_str(&utils::to_lower_case(c));
+                    } else {
+                        desc.push(c);
+                    }
+                }
+            }
+            desc
+        };
+        (word, desc)
+    }
+}
+
+/// Iterator over name candidates, created by [`Enumerator::subsequences`].
+pub struct Subsequences<'a> {
+    enumerator: &'a Enumerator<'a>,
+    // The lexicon being searched.
+    lexicon_id: usize,
+    stack: Vec<State>,
+    path: Path,
+    pruned: bool,
+}
+
+impl Iterator for Subsequences<'_> {
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_min_score(0)
+    }
+}
+
+impl Subsequences<'_> {
+    /// Returns the next candidate, skipping the states that cannot reach `min_score`.
+    fn next_with_min_score(&mut self, min_score: u64) -> Option<Match> {
+        let enumerator = self.enumerator;
+        loop {
+            let state = match self.stack.pop() {
+                Some(state) => state,
+                None => {
+                    // Moves on to the next lexicon.
+                    self.lexicon_id += 1;
+                    if self.lexicon_id == enumerator.sources.len() {
+                        return None;
+                    }
+                    self.stack.push(State::root());
+                    continue;
+                }
+            };
+            let max_score = state.score + enumerator.rest_sum(&state, &enumerator.rest_scores);
+            if enumerator.max_score(self.lexicon_id, max_score, state.num_insertions) < min_score {
+                self.pruned = true;
+                continue;
+            }
+            if let Some(m) =
+                enumerator.expand(self.lexicon_id, state, &mut self.stack, &mut self.path)
+            {
+                return Some(m);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enumerate() {
+        let words = &["aa", "abaab", "abb", "bab", "bb", "bbb", "cbab", "ccbab"];
+        let lex = Lexicon::new(words).unwrap();
+        let text = "abAaB";
+
+        let enumerator = Enumerator::new(&lex, text).unwrap();
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        let expected = vec![
+            Match {
+                word_id: 1,
+                lexicon_id: 0,
+                score: 31,
+                positions: vec![0, 1, 2, 3, 4],
+                prefix: "".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
+            }, // "abAaB"
+            Match {
+                word_id: 3,
+                lexicon_id: 0,
+                score: 13,
+                positions: vec![1, 2, 4],
+                prefix: "".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
+            }, // "bAB"
+        ];
+        assert_eq!(&matched, &expected);
+
+        assert_eq!(
+            enumerator.format_match(&matched[0]),
+            ("abaab".to_string(), "ABAAB".to_string())
+        );
+        assert_eq!(
+            enumerator.format_match(&matched[1]),
+            ("bab".to_string(), "aBAaB".to_string())
+        );
+    }
+
+    #[test]
+    fn test_enumerate_1() {
+        let words = &["aa", "abaab", "abb", "bab", "bb", "bbb", "cbab", "ccbab"];
+        let lex = Lexicon::new(words).unwrap();
+        let text = "abAaB";
+
+        let enumerator = Enumerator::new(&lex, text).unwrap().prefix_len(1).unwrap();
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        let expected = vec![
+            Match {
+                word_id: 1,
+                lexicon_id: 0,
+                score: 31,
+                positions: vec![0, 1, 2, 3, 4],
+                prefix: "".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
+            }, // "abAaB"
+            Match {
+                word_id: 3,
+                lexicon_id: 0,
+                score: 13,
+                positions: vec![1, 2, 4],
+                prefix: "".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
+            }, // "bAB"
+            Match {
+                word_id: 6,
+                lexicon_id: 0,
+                score: 13,
+                positions: vec![1, 2, 4],
+                prefix: "c".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
+            }, // "c|bAB"
+        ];
+        assert_eq!(matched, expected);
+
+        assert_eq!(
+            enumerator.format_match(&matched[0]),
+            ("abaab".to_string(), "ABAAB".to_string())
+        );
+        assert_eq!(
+            enumerator.format_match(&matched[1]),
+            ("bab".to_string(), "aBAaB".to_string())
+        );
+        assert_eq!(
+            enumerator.format_match(&matched[2]),
+            ("Cbab".to_string(), "aBAaB".to_string())
+        );
     }
 
     #[test]
@@ -351,31 +1489,59 @@ mod tests {
         let text = "abAaB";
 
         let enumerator = Enumerator::new(&lex, text).unwrap().prefix_len(2).unwrap();
-        let matched = enumerator.all_subsequences().unwrap();
+        let matched = enumerator.all_subsequences().unwrap().matches;
         let expected = vec![
             Match {
                 word_id: 1,
+                lexicon_id: 0,
                 score: 31,
-                positions: 0b11111,
+                positions: vec![0, 1, 2, 3, 4],
                 prefix: "".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
             }, // "abAaB"
             Match {
                 word_id: 3,
+                lexicon_id: 0,
                 score: 13,
-                positions: 0b10110,
+                positions: vec![1, 2, 4],
                 prefix: "".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
             }, // "bAB"
             Match {
                 word_id: 6,
+                lexicon_id: 0,
                 score: 13,
-                positions: 0b10110,
+                positions: vec![1, 2, 4],
                 prefix: "c".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
             }, // "c|bAB"
             Match {
                 word_id: 7,
+                lexicon_id: 0,
                 score: 13,
-                positions: 0b10110,
+                positions: vec![1, 2, 4],
                 prefix: "cc".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
             }, // "cc|bAB"
         ];
         assert_eq!(matched, expected);
@@ -399,21 +1565,1090 @@ mod tests {
     }
 
     #[test]
-    fn test_build_score() {
-        let text = "ab abc a".as_bytes();
-        let scores = Enumerator::build_scores(text);
-        assert_eq!(scores, vec![4, 2, 0, 4, 2, 1, 0, 4]);
+    fn test_subsequences() {
+        let words = &["aa", "abaab", "abb", "bab", "bb", "bbb", "cbab", "ccbab"];
+        let lex = Lexicon::new(words).unwrap();
+        let text = "abAaB";
+
+        let enumerator = Enumerator::new(&lex, text).unwrap().prefix_len(1).unwrap();
+        let mut matched: Vec<_> = enumerator.subsequences().unwrap().collect();
+        matched.sort_by(|m1, m2| {
+            m1.word_id
+                .cmp(&m2.word_id)
+                .then_with(|| m2.score.cmp(&m1.score))
+        });
+        let expected = vec![
+            Match {
+                word_id: 1,
+                lexicon_id: 0,
+                score: 31,
+                positions: vec![0, 1, 2, 3, 4],
+                prefix: "".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
+            }, // "abAaB"
+            Match {
+                word_id: 1,
+                lexicon_id: 0,
+                score: 15,
+                positions: vec![1, 2, 3, 4],
+                prefix: "a".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
+            }, // "a|bAaB"
+            Match {
+                word_id: 3,
+                lexicon_id: 0,
+                score: 13,
+                positions: vec![1, 2, 4],
+                prefix: "".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
+            }, // "bAB"
+            Match {
+                word_id: 3,
+                lexicon_id: 0,
+                score: 5,
+                positions: vec![2, 4],
+                prefix: "b".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
+            }, // "b|AB"
+            Match {
+                word_id: 6,
+                lexicon_id: 0,
+                score: 13,
+                positions: vec![1, 2, 4],
+                prefix: "c".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
+            }, // "c|bAB"
+        ];
+        assert_eq!(matched, expected);
+
+        // Stops the iteration early.
+        let matched: Vec<_> = enumerator.subsequences().unwrap().take(2).collect();
+        assert_eq!(matched.len(), 2);
+    }
+
+    #[test]
+    fn test_long_text() {
+        let words = &["aa", "abaab", "abb", "bab", "bb", "bbb", "cbab", "ccbab"];
+        let lex = Lexicon::new(words).unwrap();
+        let text = format!("{}BAB", "x ".repeat(100));
+
+        let enumerator = Enumerator::new(&lex, &text).unwrap();
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        assert_eq!(matched.len(), 1);
+        assert_eq!(matched[0].word_id, 3);
+        assert_eq!(matched[0].positions, vec![200, 201, 202]);
+        assert_eq!(
+            enumerator.format_match(&matched[0]),
+            ("bab".to_string(), format!("{}BAB", "x ".repeat(100)))
+        );
     }
 
     #[test]
-    fn test_prefix() {
-        let mut prefix = Prefix::new();
-        assert_eq!(prefix.len(), 0);
-
-        prefix = prefix.push(b'a');
-        prefix = prefix.push(b'b');
-        prefix = prefix.push(b'c');
-        assert_eq!(prefix.len(), 3);
-        assert_eq!(prefix.string(), "abc");
+    fn test_scorer() {
+        // Scores the number of matched letters, preferring shorter words.
+        struct LengthScorer;
+
+        impl Scorer for LengthScorer {
+            fn position_scores(&self, text: &str, _: &[Range<usize>]) -> Result<Vec<u64>> {
+                Ok(vec![10; text.len()])
+            }
+
+            fn candidate_score(&self, cand: &Candidate) -> u64 {
+                cand.matched.score - cand.word.len() as u64
+            }
+        }
+
+        let words = &["aa", "abaab", "abb", "bab", "bb", "bbb", "cbab", "ccbab"];
+        let lex = Lexicon::new(words).unwrap();
+        let text = "abaab";
+
+        let enumerator = Enumerator::new(&lex, text)
+            .unwrap()
+            .scorer(LengthScorer)
+            .unwrap();
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        let expected = vec![
+            Match {
+                word_id: 1,
+                lexicon_id: 0,
+                score: 45,
+                positions: vec![0, 1, 2, 3, 4],
+                prefix: "".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
+            }, // "abaab"
+            Match {
+                word_id: 2,
+                lexicon_id: 0,
+                score: 27,
+                positions: vec![0, 1, 4],
+                prefix: "".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
+            }, // "aBaaB"
+            Match {
+                word_id: 3,
+                lexicon_id: 0,
+                score: 27,
+                positions: vec![1, 3, 4],
+                prefix: "".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
+            }, // "aBaAB"
+            Match {
+                word_id: 0,
+                lexicon_id: 0,
+                score: 18,
+                positions: vec![2, 3],
+                prefix: "".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
+            }, // "abAAb"
+            Match {
+                word_id: 4,
+                lexicon_id: 0,
+                score: 18,
+                positions: vec![1, 4],
+                prefix: "".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
+            }, // "aBaaB"
+        ];
+        assert_eq!(matched, expected);
+        assert_eq!(enumerator.top_k(3).unwrap(), &expected[..3]);
+    }
+
+    #[test]
+    fn test_weight_ratio() {
+        let words = &["aa", "abaab", "abb", "bab", "bb", "bbb", "cbab", "ccbab"];
+        let weights = &[0.0, 0.0, 0.0, 1.0, 4.0, 0.0, 0.0, 0.0];
+        let lex = Lexicon::with_weights(words.iter().zip(weights.iter().cloned())).unwrap();
+        let text = "abAaB";
+
+        let enumerator = Enumerator::new(&lex, text)
+            .unwrap()
+            .weight_ratio(3.0)
+            .unwrap();
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        let expected = vec![
+            Match {
+                word_id: 1,
+                lexicon_id: 0,
+                score: 31,
+                positions: vec![0, 1, 2, 3, 4],
+                prefix: "".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
+            }, // "abAaB"
+            Match {
+                word_id: 3,
+                lexicon_id: 0,
+                score: 22,
+                positions: vec![1, 2, 4],
+                prefix: "".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
+            }, // "bAB" (13 * 1.75)
+        ];
+        assert_eq!(matched, expected);
+        assert_eq!(enumerator.top_k(1).unwrap(), &expected[..1]);
+
+        let lex = Lexicon::new(words).unwrap();
+        assert!(Enumerator::new(&lex, text)
+            .unwrap()
+            .weight_ratio(3.0)
+            .is_err());
+    }
+
+    #[test]
+    fn test_pronounceability() {
+        let words = &["aa", "abaab", "abb", "bab", "bb", "bbb", "cbab", "ccbab"];
+        let lex = Lexicon::new(words).unwrap();
+        let model = Pronounceability::new(&lex);
+        let text = "abaab";
+
+        let enumerator = Enumerator::new(&lex, text)
+            .unwrap()
+            .pronounceability(&model)
+            .pronounceability_ratio(2.0)
+            .unwrap();
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        for m in &matched {
+            assert_eq!(m.pronounceability, Some(model.score(lex.word(m.word_id))));
+        }
+        assert_eq!(enumerator.top_k(2).unwrap(), &matched[..2]);
+
+        let min_pronounceability = model.score("bab");
+        let enumerator = enumerator
+            .min_pronounceability(min_pronounceability)
+            .unwrap();
+        let filtered = enumerator.all_subsequences().unwrap().matches;
+        let expected: Vec<_> = matched
+            .into_iter()
+            .filter(|m| m.pronounceability.unwrap() >= min_pronounceability)
+            .collect();
+        assert!(!filtered.is_empty());
+        assert_eq!(filtered, expected);
+
+        assert!(Enumerator::new(&lex, text)
+            .unwrap()
+            .min_pronounceability(0.5)
+            .is_err());
+        for min_pronounceability in [f64::NAN, -0.1, 1.1] {
+            assert!(Enumerator::new(&lex, text)
+                .unwrap()
+                .pronounceability(&model)
+                .min_pronounceability(min_pronounceability)
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn test_display_forms() {
+        let lex = Lexicon::new(["github", "hub"])
+            .unwrap()
+            .display_forms(["GitHub", "hub"])
+            .unwrap();
+        let text = "git hub";
+
+        let enumerator = Enumerator::new(&lex, text).unwrap();
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        assert_eq!(
+            enumerator.format_match(&matched[0]),
+            ("github".to_string(), "GIT HUB".to_string())
+        );
+
+        let enumerator = enumerator.display_forms(true);
+        assert_eq!(
+            enumerator.format_match(&matched[0]),
+            ("GitHub".to_string(), "GIT HUB".to_string())
+        );
+    }
+
+    #[test]
+    fn test_unicode() {
+        let lex = Lexicon::new(["eté", "équipe", "été"]).unwrap();
+        let text = "Été tôt";
+
+        let enumerator = Enumerator::new(&lex, text).unwrap();
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        assert_eq!(matched.len(), 1);
+        assert_eq!(matched[0].word_id, 2);
+        assert_eq!(matched[0].positions, vec![0, 2, 3]);
+        assert_eq!(
+            enumerator.format_match(&matched[0]),
+            ("été".to_string(), "ÉTÉ tôt".to_string())
+        );
+
+        // The decomposed text is normalized.
+        let text = "E\u{301}te\u{301} to\u{302}t";
+        let enumerator = Enumerator::new(&lex, text).unwrap();
+        assert_eq!(enumerator.all_subsequences().unwrap().matches, matched);
+
+        let text = "Ete equipe";
+        let enumerator = Enumerator::new(&lex, text).unwrap();
+        assert!(enumerator.all_subsequences().unwrap().matches.is_empty());
+        let enumerator = enumerator.accent_insensitive(true);
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        let words: Vec<_> = matched.iter().map(|m| lex.word(m.word_id)).collect();
+        assert_eq!(words, vec!["eté", "été", "équipe"]);
+        assert_eq!(
+            enumerator.format_match(&matched[2]),
+            ("équipe".to_string(), "Ete eQUIPE".to_string())
+        );
+    }
+
+    #[test]
+    fn test_multiple_lexicons() {
+        let lex0 = Lexicon::new(["aa", "bab"]).unwrap();
+        let lex1 = Lexicon::new(["ab", "bab"]).unwrap();
+        let text = "abAaB";
+
+        let enumerator = Enumerator::new(&lex0, text)
+            .unwrap()
+            .add_lexicon(&lex1, 2.0)
+            .unwrap();
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        let expected = vec![
+            Match {
+                word_id: 1,
+                lexicon_id: 1,
+                score: 26,
+                positions: vec![1, 2, 4],
+                prefix: "".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
+            }, // "bAB"
+            Match {
+                word_id: 0,
+                lexicon_id: 1,
+                score: 10,
+                positions: vec![2, 4],
+                prefix: "".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
+            }, // "AB"
+        ];
+        assert_eq!(&matched, &expected);
+        assert_eq!(enumerator.top_k(1).unwrap(), &expected[..1]);
+        assert_eq!(
+            enumerator.format_match(&matched[1]),
+            ("ab".to_string(), "abAaB".to_string())
+        );
+
+        let enumerator = Enumerator::new(&lex0, text)
+            .unwrap()
+            .add_lexicon(&lex1, 0.5)
+            .unwrap();
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        let sources: Vec<_> = matched.iter().map(|m| (m.lexicon_id, m.score)).collect();
+        assert_eq!(sources, vec![(0, 13), (1, 2)]);
+        assert_eq!(enumerator.top_k(2).unwrap(), matched);
+
+        assert!(Enumerator::new(&lex0, text)
+            .unwrap()
+            .add_lexicon(&lex1, 0.0)
+            .is_err());
+    }
+
+    #[test]
+    fn test_denylist() {
+        let words = &["aa", "abaab", "abb", "bab", "bb", "bbb", "cbab", "ccbab"];
+        let lex = Lexicon::new(words).unwrap();
+        let text = "abaab";
+
+        let enumerator = Enumerator::new(&lex, text).unwrap();
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        let denylist = Denylist::new(["aa", "ab?", "b*b"]);
+        let expected: Vec<_> = matched
+            .into_iter()
+            .filter(|m| !denylist.contains(lex.word(m.word_id)))
+            .collect();
+        assert_eq!(expected.len(), 1);
+
+        let enumerator = enumerator.denylist(&denylist).max_matches(1).unwrap();
+        // Denied words do not count toward the limit.
+        assert_eq!(enumerator.all_subsequences().unwrap().matches, expected);
+    }
+
+    #[test]
+    fn test_registry() {
+        let words = &["aa", "abaab", "abb", "bab", "bb", "bbb", "cbab", "ccbab"];
+        let lex = Lexicon::new(words).unwrap();
+        let registry = Registry::new(["abaab", "b-a-b", "abab"]).unwrap();
+        let text = "abAaB";
+
+        let enumerator = Enumerator::new(&lex, text).unwrap().registry(&registry);
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        let availabilities: Vec<_> = matched.iter().map(|m| m.availability).collect();
+        assert_eq!(
+            availabilities,
+            vec![Some(Availability::Taken), Some(Availability::Similar)]
+        );
+
+        let conflicts: Vec<_> = matched.iter().map(|m| m.conflicts.len()).collect();
+        assert_eq!(conflicts, vec![0, 0]);
+        let enumerator = enumerator.conflicts(1, 2).unwrap();
+        let conflicts: Vec<_> = enumerator
+            .all_subsequences()
+            .unwrap()
+            .matches
+            .into_iter()
+            .map(|m| m.conflicts)
+            .collect();
+        let conflict = |name: &str, distance| Conflict {
+            name: name.to_string(),
+            distance,
+        };
+        assert_eq!(
+            conflicts,
+            vec![
+                vec![conflict("abaab", 0), conflict("abab", 1)],
+                vec![conflict("abab", 1)]
+            ]
+        );
+        assert_eq!(enumerator.top_k(1).unwrap()[0].conflicts, conflicts[0]);
+        assert!(enumerator
+            .subsequences()
+            .unwrap()
+            .all(|m| m.conflicts.is_empty()));
+
+        let enumerator = enumerator
+            .hide_taken(true)
+            .unwrap()
+            .conflicts(0, 0)
+            .unwrap();
+        let filtered = enumerator.all_subsequences().unwrap().matches;
+        assert_eq!(filtered, &matched[1..]);
+        assert_eq!(enumerator.top_k(1).unwrap(), &matched[1..]);
+
+        assert!(Enumerator::new(&lex, text)
+            .unwrap()
+            .hide_taken(true)
+            .is_err());
+        assert!(Enumerator::new(&lex, text)
+            .unwrap()
+            .conflicts(1, 1)
+            .is_err());
+    }
+
+    #[test]
+    fn test_suffix() {
+        let words = &["aa", "abaab", "abb", "bab", "bb", "bbb", "cbab", "ccbab"];
+        let lex = Lexicon::new(words).unwrap();
+        let text = "aBaB";
+
+        let enumerator = Enumerator::new(&lex, text).unwrap().suffix_len(2).unwrap();
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        let expected = vec![
+            Match {
+                word_id: 2,
+                lexicon_id: 0,
+                score: 13,
+                positions: vec![0, 1, 3],
+                prefix: "".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
+            },
+            Match {
+                word_id: 3,
+                lexicon_id: 0,
+                score: 7,
+                positions: vec![1, 2, 3],
+                prefix: "".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
+            },
+            Match {
+                word_id: 4,
+                lexicon_id: 0,
+                score: 5,
+                positions: vec![1, 3],
+                prefix: "".to_string(),
+                suffix: "".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
+            },
+            Match {
+                word_id: 5,
+                lexicon_id: 0,
+                score: 5,
+                positions: vec![1, 3],
+                prefix: "".to_string(),
+                suffix: "b".to_string(),
+                insertions: vec![],
+                order: vec![],
+                pronounceability: None,
+                availability: None,
+                conflicts: vec![],
+            },
+        ];
+        assert_eq!(matched, expected);
+        assert_eq!(
+            enumerator.format_match(&matched[3]),
+            ("bbB".to_string(), "aBaB".to_string())
+        );
+
+        assert!(Enumerator::new(&lex, text)
+            .unwrap()
+            .suffix_len(MAX_AFFIX_LEN + 1)
+            .is_err());
+    }
+
+    #[test]
+    fn test_insertions() {
+        let words = &["aa", "abaab", "abb", "bab", "bb", "bbb", "cbab", "ccbab"];
+        let lex = Lexicon::new(words).unwrap();
+        let text = "aBB";
+
+        let enumerator = Enumerator::new(&lex, text)
+            .unwrap()
+            .insertions(2, 0.5)
+            .unwrap();
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        let formatted: Vec<_> = matched
+            .iter()
+            .map(|m| (enumerator.format_match(m).0, m.insertions.clone(), m.score))
+            .collect();
+        assert_eq!(
+            formatted,
+            vec![
+                ("abb".to_string(), vec![], 7),
+                ("bb".to_string(), vec![], 3),
+                ("abAAb".to_string(), vec![2, 3], 1),
+                ("bAb".to_string(), vec![1], 1),
+                ("bBb".to_string(), vec![1], 1),
+            ]
+        );
+        assert_eq!(
+            enumerator.format_match(&matched[2]),
+            ("abAAb".to_string(), "ABB".to_string())
+        );
+
+        // The inserted letters are neither before nor after the acronym.
+        let enumerator = Enumerator::new(&lex, text)
+            .unwrap()
+            .insertions(1, 0.0)
+            .unwrap();
+        let words: Vec<_> = enumerator
+            .top_k(10)
+            .unwrap()
+            .iter()
+            .map(|m| (enumerator.word(m), m.score))
+            .collect();
+        assert_eq!(words, vec![("abb", 7), ("bab", 3), ("bb", 3), ("bbb", 3)]);
+
+        assert!(Enumerator::new(&lex, text)
+            .unwrap()
+            .insertions(1, 1.5)
+            .is_err());
+    }
+
+    #[test]
+    fn test_anagram() {
+        let words = &["abc", "ac", "bad", "ca", "cab", "cda", "dab", "dac"];
+        let lex = Lexicon::new(words).unwrap();
+        let text = "ab c da";
+
+        let enumerator = Enumerator::new(&lex, text).unwrap().anagram(true);
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        let scores: HashMap<_, _> = matched
+            .iter()
+            .map(|m| (enumerator.word(m), m.score))
+            .collect();
+
+        // The same as the best candidates of all the permuted texts.
+        let mut expected = HashMap::new();
+        for order in [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ] {
+            let permuted = order.map(|i| ["ab", "c", "da"][i]).join(" ");
+            let enumerator = Enumerator::new(&lex, &permuted).unwrap();
+            for m in enumerator.all_subsequences().unwrap().matches {
+                let score = expected.entry(lex.word(m.word_id)).or_insert(0);
+                *score = m.score.max(*score);
+            }
+        }
+        assert_eq!(scores, expected);
+        assert_eq!(enumerator.top_k(3).unwrap(), &matched[..3]);
+
+        let m = matched
+            .iter()
+            .find(|m| enumerator.word(m) == "cab")
+            .unwrap();
+        assert_eq!(m.positions, vec![0, 1, 3]);
+        assert_eq!(m.order, vec![1, 0, 2]);
+        assert_eq!(
+            enumerator.format_match(m),
+            ("cab".to_string(), "C AB da".to_string())
+        );
+
+        // The markup is dropped from the rearranged words.
+        let enumerator = Enumerator::new(&lex, "ab^2 c!").unwrap().anagram(true);
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        let m = matched
+            .iter()
+            .find(|m| enumerator.word(m) == "cab")
+            .unwrap();
+        assert_eq!(
+            enumerator.format_match(m),
+            ("cab".to_string(), "C AB".to_string())
+        );
+
+        let enumerator = Enumerator::new(&lex, "a b c d e f g h i")
+            .unwrap()
+            .anagram(true);
+        assert!(enumerator.all_subsequences().is_err());
+        assert!(enumerator.top_k(1).is_err());
+        assert!(enumerator.subsequences().is_err());
+    }
+
+    #[test]
+    fn test_stopwords() {
+        let words = &[
+            "at", "fan", "ta", "tan", "tang", "tin", "ting", "toga", "ton", "tong",
+        ];
+        let lex = Lexicon::new(words).unwrap();
+        let stopwords = Stopwords::english();
+
+        // The same candidates as those of the text without the stopwords.
+        let enumerator = Enumerator::new(&lex, "a tool for the naming")
+            .unwrap()
+            .stopwords(&stopwords)
+            .unwrap()
+            .constraint(Constraint::UseEveryWord)
+            .unwrap();
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        let expected = Enumerator::new(&lex, "tool naming").unwrap();
+        let expected = expected.all_subsequences().unwrap().matches;
+        let words = |matches: &[Match]| -> Vec<_> {
+            matches
+                .iter()
+                .map(|m| (lex.word(m.word_id), m.score))
+                .collect()
+        };
+        assert!(!matched.is_empty());
+        assert_eq!(words(&matched), words(&expected));
+        assert_eq!(
+            enumerator.format_match(&matched[0]),
+            ("tong".to_string(), "a TOol for the NaminG".to_string())
+        );
+
+        // The stopwords and the constraints can be given in any order.
+        for constraint in [
+            Constraint::UseEveryWord,
+            Constraint::StartsWith(0),
+            Constraint::UseWord(0),
+            Constraint::UseWord(3),
+            Constraint::MinLength(3),
+        ] {
+            let enumerator = Enumerator::new(&lex, "a tool for the naming").unwrap();
+            let matched = enumerator
+                .stopwords(&stopwords)
+                .unwrap()
+                .constraint(constraint)
+                .unwrap()
+                .all_subsequences()
+                .unwrap()
+                .matches;
+            let enumerator = Enumerator::new(&lex, "a tool for the naming").unwrap();
+            let expected = enumerator
+                .constraint(constraint)
+                .unwrap()
+                .stopwords(&stopwords)
+                .unwrap()
+                .all_subsequences()
+                .unwrap()
+                .matches;
+            assert_eq!(matched, expected);
+        }
+
+        // The stopwords given in the constraints are used.
+        let enumerator = Enumerator::new(&lex, "a tool for the naming")
+            .unwrap()
+            .constraint(Constraint::UseWord(0))
+            .unwrap()
+            .stopwords(&stopwords)
+            .unwrap();
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        assert!(!matched.is_empty());
+        assert!(matched.iter().all(|m| m.positions[0] == 0));
+        assert_eq!(enumerator.stopwords, vec![false, false, true, true, false]);
+
+        // The anagram mode counts the words except for the stopwords given later.
+        let text = "a tool for the naming of it in to";
+        let enumerator = Enumerator::new(&lex, text).unwrap().anagram(true);
+        assert!(enumerator.all_subsequences().is_err());
+        let enumerator = enumerator.stopwords(&stopwords).unwrap();
+        assert!(enumerator.all_subsequences().is_ok());
+
+        // The stopwords in UPPERCASE are used.
+        let enumerator = Enumerator::new(&lex, "A tool for the naming")
+            .unwrap()
+            .stopwords(&stopwords)
+            .unwrap();
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        assert!(matched.iter().all(|m| m.positions[0] == 0));
+        assert_eq!(enumerator.stopwords, vec![false, false, true, true, false]);
+
+        // The words after more than 64 stopwords are permuted.
+        let text = format!("{}tool naming", "the ".repeat(70));
+        let enumerator = Enumerator::new(&lex, &text)
+            .unwrap()
+            .stopwords(&stopwords)
+            .unwrap()
+            .anagram(true);
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        let expected = Enumerator::new(&lex, "tool naming").unwrap().anagram(true);
+        let expected = expected.all_subsequences().unwrap().matches;
+        assert!(!matched.is_empty());
+        assert_eq!(words(&matched), words(&expected));
+        assert!(matched.iter().all(|m| m.order[0] >= 70));
+
+        // The stopwords given again replace the previous ones.
+        let lex = Lexicon::new(["ab", "tb", "tob"]).unwrap();
+        let enumerator = Enumerator::new(&lex, "to ab")
+            .unwrap()
+            .stopwords(&stopwords)
+            .unwrap()
+            .stopwords(&Stopwords::new(["xyz"]))
+            .unwrap();
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        let mut words: Vec<_> = matched.iter().map(|m| lex.word(m.word_id)).collect();
+        words.sort_unstable();
+        assert_eq!(words, vec!["ab", "tb", "tob"]);
+    }
+
+    #[test]
+    fn test_tokenizer() {
+        let words = &["a-d", "abc", "ad", "bcd", "bd", "cd"];
+        let lex = Lexicon::new(words).unwrap();
+        let words = |matches: &[Match]| -> Vec<_> {
+            matches
+                .iter()
+                .map(|m| (lex.word(m.word_id), m.score))
+                .collect()
+        };
+
+        // The separators are skipped, and never matched with the words.
+        let enumerator = Enumerator::new(&lex, "a-b_c/, d.").unwrap();
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        let expected = Enumerator::new(&lex, "a b  c d").unwrap();
+        let expected = expected.all_subsequences().unwrap().matches;
+        assert!(!matched.is_empty());
+        assert_eq!(words(&matched), words(&expected));
+        assert!(matched.iter().all(|m| lex.word(m.word_id) != "a-d"));
+        assert_eq!(
+            enumerator.format_match(&matched[0]),
+            ("abc".to_string(), "A-B_C/, d.".to_string())
+        );
+
+        // The camelCase words are split, and their first UPPERCASE letters are not forced.
+        let tokenizer = Tokenizer::new().camel_case(true);
+        let enumerator = Enumerator::with_tokenizer(&lex, "AbCd", tokenizer).unwrap();
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        assert_eq!(enumerator.segments, vec![0..2, 2..4]);
+        let camel = Enumerator::new(&lex, "Ab cd").unwrap();
+        assert_eq!(
+            words(&matched),
+            words(&camel.all_subsequences().unwrap().matches)
+        );
+        let m = matched
+            .iter()
+            .find(|m| lex.word(m.word_id) == "ad")
+            .unwrap();
+        assert_eq!(
+            enumerator.format_match(m),
+            ("ad".to_string(), "AbcD".to_string())
+        );
+        let enumerator = Enumerator::with_tokenizer(&lex, "aB-cD", tokenizer).unwrap();
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        assert_eq!(enumerator.segments, vec![0..1, 1..2, 3..4, 4..5]);
+        assert_eq!(words(&matched), words(&expected));
+        let enumerator = Enumerator::new(&lex, "aB-cD").unwrap();
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        assert_eq!(enumerator.segments, vec![0..2, 3..5]);
+        let matched: Vec<_> = matched.iter().map(|m| lex.word(m.word_id)).collect();
+        assert_eq!(matched, vec!["bcd", "bd"]);
+    }
+
+    #[test]
+    fn test_annotations() {
+        let words = &[
+            "ab", "abc", "abcd", "ac", "acd", "bc", "bcd", "bd", "cab", "cd",
+        ];
+        let lex = Lexicon::new(words).unwrap();
+        let words = |matches: &[Match]| -> Vec<_> {
+            matches
+                .iter()
+                .map(|m| (lex.word(m.word_id), m.score))
+                .collect()
+        };
+
+        // The scores of the boosted word are multiplied.
+        let enumerator = Enumerator::new(&lex, "ab^2 cd").unwrap();
+        assert_eq!(enumerator.scores, vec![4, 2, 0, 0, 0, 2, 1]);
+        assert_eq!(enumerator.rest_scores, vec![9, 5, 3, 3, 3, 3, 1, 0]);
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        assert_eq!(
+            enumerator.format_match(&matched[0]),
+            ("abcd".to_string(), "AB^2 CD".to_string())
+        );
+
+        // The required word is the same as Constraint::UseWord.
+        let enumerator = Enumerator::new(&lex, "ab cd!").unwrap();
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        let expected = Enumerator::new(&lex, "ab cd")
+            .unwrap()
+            .constraint(Constraint::UseWord(1))
+            .unwrap();
+        let expected = expected.all_subsequences().unwrap().matches;
+        assert!(!matched.is_empty());
+        assert_eq!(words(&matched), words(&expected));
+
+        // The optional word can be skipped even in UPPERCASE and with Constraint::UseEveryWord.
+        let enumerator = Enumerator::new(&lex, "AB? cd")
+            .unwrap()
+            .constraint(Constraint::UseEveryWord)
+            .unwrap();
+        let matched = enumerator.all_subsequences().unwrap().matches;
+        assert_eq!(words(&matched), words(&expected));
+        let m = matched
+            .iter()
+            .find(|m| lex.word(m.word_id) == "bd")
+            .unwrap();
+        assert_eq!(
+            enumerator.format_match(m),
+            ("bd".to_string(), "aB? cD".to_string())
+        );
+
+        // The annotated stopwords are not ignored.
+        let enumerator = Enumerator::new(&lex, "a! cd")
+            .unwrap()
+            .stopwords(&Stopwords::english())
+            .unwrap();
+        assert_eq!(enumerator.stopwords, vec![false, false]);
+
+        assert!(Enumerator::new(&lex, "ab^ cd").is_err());
+        assert!(Enumerator::new(&lex, "ab^0 cd").is_err());
+        assert!(Enumerator::new(&lex, "ab^17 cd").is_err());
+        assert!(Enumerator::new(&lex, "ab!? cd").is_err());
+    }
+
+    #[test]
+    fn test_constraints() {
+        let words = &[
+            "ab", "abc", "abcd", "ac", "acd", "bc", "bcd", "bd", "cab", "cd",
+        ];
+        let lex = Lexicon::new(words).unwrap();
+        let text = "ab c d";
+
+        let enumerator = Enumerator::new(&lex, text).unwrap().prefix_len(1).unwrap();
+        let candidates = |enumerator: &Enumerator| -> Vec<_> {
+            enumerator
+                .subsequences()
+                .unwrap()
+                .map(|m| (m.word_id, m.prefix, m.positions))
+                .collect()
+        };
+        let all = candidates(&enumerator);
+        let check = |constraints: &[Constraint], pred: &dyn Fn(&str, &[usize]) -> bool| {
+            let mut enumerator = Enumerator::new(&lex, text).unwrap().prefix_len(1).unwrap();
+            for &constraint in constraints {
+                enumerator = enumerator.constraint(constraint).unwrap();
+            }
+            let expected: Vec<_> = all
+                .iter()
+                .filter(|(_, prefix, positions)| pred(prefix, positions))
+                .cloned()
+                .collect();
+            assert!(!expected.is_empty() && expected.len() < all.len());
+            assert_eq!(candidates(&enumerator), expected);
+        };
+        check(&[Constraint::StartsWith(1)], &|_, positions| {
+            positions[0] == 3
+        });
+        check(&[Constraint::UseWord(1)], &|_, positions| {
+            positions.contains(&3)
+        });
+        check(&[Constraint::UseEveryWord], &|_, positions| {
+            positions.iter().any(|&pos| pos < 2) && positions.contains(&3) && positions.contains(&5)
+        });
+        check(&[Constraint::MinLength(3)], &|prefix, positions| {
+            prefix.len() + positions.len() >= 3
+        });
+        check(&[Constraint::MaxLength(2)], &|prefix, positions| {
+            prefix.len() + positions.len() <= 2
+        });
+        check(
+            &[Constraint::StartsWith(0), Constraint::MaxLength(3)],
+            &|prefix, positions| positions[0] == 0 && prefix.len() + positions.len() <= 3,
+        );
+
+        let enumerator = Enumerator::new(&lex, text).unwrap();
+        assert!(enumerator.constraint(Constraint::UseWord(3)).is_err());
+        let enumerator = Enumerator::new(&lex, text).unwrap();
+        assert!(enumerator
+            .constraint(Constraint::MinLength(3))
+            .unwrap()
+            .constraint(Constraint::MaxLength(2))
+            .is_err());
+    }
+
+    #[test]
+    fn test_length() {
+        let words = &["aa", "abaab", "abb", "bab", "bb", "bbb", "cbab", "ccbab"];
+        let lex = Lexicon::new(words).unwrap();
+        let text = "abaab";
+
+        let enumerator = Enumerator::new(&lex, text).unwrap().prefix_len(2).unwrap();
+        let all = enumerator.all_subsequences().unwrap().matches;
+        for (min_len, max_len) in [(0, 2), (3, 3), (4, 5), (5, 7), (6, 7)] {
+            let enumerator = Enumerator::new(&lex, text)
+                .unwrap()
+                .prefix_len(2)
+                .unwrap()
+                .min_len(min_len)
+                .unwrap()
+                .max_len(max_len)
+                .unwrap();
+            let matched = enumerator.all_subsequences().unwrap().matches;
+            let expected: Vec<_> = all
+                .iter()
+                .filter(|m| (min_len..=max_len).contains(&enumerator.word(m).len()))
+                .cloned()
+                .collect();
+            assert_eq!(matched, expected);
+        }
+
+        // No states are expanded if the text is too short.
+        let enumerator = Enumerator::new(&lex, text).unwrap().min_len(6).unwrap();
+        assert!(enumerator.subsequences().unwrap().next().is_none());
+
+        // The maximum length avoids too many matches.
+        let enumerator = Enumerator::new(&lex, text)
+            .unwrap()
+            .prefix_len(2)
+            .unwrap()
+            .max_matches(3)
+            .unwrap();
+        assert!(enumerator.all_subsequences().is_err());
+        let matched = enumerator.max_len(2).unwrap().all_subsequences().unwrap();
+        assert_eq!(matched.matches.len(), 2);
+    }
+
+    #[test]
+    fn test_top_k() {
+        let words = &["aa", "abaab", "abb", "bab", "bb", "bbb", "cbab", "ccbab"];
+        let lex = Lexicon::new(words).unwrap();
+        let text = "abaab";
+
+        let enumerator = Enumerator::new(&lex, text).unwrap().prefix_len(2).unwrap();
+        let expected = enumerator.all_subsequences().unwrap().matches;
+        assert!(expected.len() > 3);
+        for k in 0..=expected.len() + 1 {
+            let matched = enumerator.top_k(k).unwrap();
+            assert_eq!(matched, &expected[..k.min(expected.len())]);
+        }
+    }
+
+    #[test]
+    fn test_limit_policy() {
+        let words = &["aa", "abaab", "abb", "bab", "bb", "bbb", "cbab", "ccbab"];
+        let lex = Lexicon::new(words).unwrap();
+        let text = "abaab";
+
+        let enumerator = Enumerator::new(&lex, text).unwrap().prefix_len(2).unwrap();
+        let expected = enumerator.all_subsequences().unwrap();
+        assert!(expected.complete);
+        let num_matches = expected.matches.len();
+
+        let enumerator = enumerator.max_matches(num_matches).unwrap();
+        assert_eq!(enumerator.all_subsequences().unwrap(), expected);
+
+        let enumerator = enumerator.max_matches(num_matches - 1).unwrap();
+        assert!(enumerator.all_subsequences().is_err());
+
+        let enumerator = enumerator.limit_policy(LimitPolicy::Truncate);
+        let matched = enumerator.all_subsequences().unwrap();
+        assert!(!matched.complete);
+        assert_eq!(matched.matches.len(), num_matches - 1);
+
+        let enumerator = enumerator.limit_policy(LimitPolicy::KeepBest);
+        let matched = enumerator.all_subsequences().unwrap();
+        assert!(!matched.complete);
+        assert_eq!(matched.matches, &expected.matches[..num_matches - 1]);
+
+        assert!(enumerator.max_matches(0).is_err());
+    }
+
+    #[test]
+    fn test_build_rest_scores() {
+        let rest_scores = Enumerator::build_rest_scores(&[4, 2, 0, 4, 2, 1, 0, 4]).unwrap();
+        assert_eq!(rest_scores, vec![17, 13, 11, 11, 7, 5, 4, 4, 0]);
+        assert!(Enumerator::build_rest_scores(&[u64::MAX, 1]).is_err());
+    }
+
+    #[test]
+    fn test_score_limits() {
+        let lex = Lexicon::new(["ab"]).unwrap();
+        assert!(Enumerator::new(&lex, "internationalization").is_ok());
+
+        let word = format!("{}^16", "a".repeat(48));
+        assert!(Enumerator::new(&lex, &word).is_ok());
+        assert!(Enumerator::new(&lex, &vec![word.as_str(); 100].join(" ")).is_ok());
+        assert!(Enumerator::new(&lex, &vec![word.as_str(); 4200].join(" ")).is_err());
+
+        let word = "a".repeat(49);
+        assert!(Enumerator::new(&lex, &word).is_err());
+    }
+
+    #[test]
+    fn test_affix() {
+        let mut affix = Affix::new();
+        assert_eq!(affix.len(), 0);
+
+        affix = affix.push(b'a');
+        affix = affix.push(b'b');
+        affix = affix.push(b'c');
+        assert_eq!(affix.len(), 3);
+        assert_eq!(affix.string(), "abc");
     }
 }
@@ -1,32 +1,432 @@
-use anyhow::Result;
+use std::borrow::Cow;
+use std::io::{Read, Write};
+
+use anyhow::{anyhow, Result};
 
 use crate::trie::Trie;
+use crate::utils;
+
+// Header of the serialized lexicon.
+const MAGIC: &[u8; 4] = b"GNLX";
+// Version of the serialization format, to be incremented when it changes.
+const VERSION: u32 = 2;
 
 /// Word lexicon.
-pub struct Lexicon {
-    words: Vec<String>,
-    trie: Trie,
+///
+/// The data is held in the serialized layout so that it can be borrowed
+/// from serialized bytes without copying (see [`Lexicon::from_bytes`]).
+pub struct Lexicon<'a> {
+    words: Strings<'a>,
+    // Weights of words (little-endian f64 each).
+    weights: Option<Cow<'a, [u8]>>,
+    displays: Option<Strings<'a>>,
+    trie: Trie<'a>,
 }
 
-impl Lexicon {
+impl<'a> Lexicon<'a> {
     /// Creates an instance from a set of words.
+    ///
+    /// The words must be in lowercase and normalized into NFC, as done by [`LexiconBuilder`](crate::LexiconBuilder).
     pub fn new<I, W>(words: I) -> Result<Self>
     where
         I: IntoIterator<Item = W>,
         W: AsRef<str>,
     {
         let words: Vec<_> = words.into_iter().map(|w| w.as_ref().to_string()).collect();
-        let trie = Trie::from_words(&words)?;
-        Ok(Self { words, trie })
+        Self::build(&words, None)
+    }
+
+    /// Creates an instance from a set of words with weights such as (log) frequencies in a corpus.
+    /// The weights must be finite and non-negative.
+    pub fn with_weights<I, W>(records: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (W, f64)>,
+        W: AsRef<str>,
+    {
+        let (words, weights): (Vec<_>, Vec<_>) = records
+            .into_iter()
+            .map(|(w, x)| (w.as_ref().to_string(), x))
+            .unzip();
+        if let Some(&x) = weights.iter().find(|&&x| !x.is_finite() || x < 0.0) {
+            return Err(anyhow!(
+                "Input weights must be finite and non-negative ({}).",
+                x
+            ));
+        }
+        Self::build(&words, Some(&weights))
+    }
+
+    fn build(words: &[String], weights: Option<&[f64]>) -> Result<Self> {
+        let trie = Trie::from_words(words)?;
+        let weights = weights.map(|weights| weights.iter().flat_map(|x| x.to_le_bytes()).collect());
+        Ok(Self {
+            words: Strings::new(words)?,
+            weights: weights.map(Cow::Owned),
+            displays: None,
+            trie,
+        })
+    }
+
+    /// Specifies the display forms of the words in order, such as `GitHub` for `github`,
+    /// which can be rendered by [`Enumerator::display_forms`](crate::Enumerator::display_forms).
+    /// The number of display forms must be the same as that of words, and each must not be empty.
+    pub fn display_forms<I, W>(mut self, displays: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = W>,
+        W: AsRef<str>,
+    {
+        let displays: Vec<_> = displays.into_iter().collect();
+        if displays.len() != self.num_words() {
+            return Err(anyhow!(
+                "The number of display forms must be the same as that of words ({} vs {}).",
+                displays.len(),
+                self.num_words()
+            ));
+        }
+        if displays.iter().any(|d| d.as_ref().is_empty()) {
+            return Err(anyhow!("Display forms must not contain an empty one."));
+        }
+        self.displays = Some(Strings::new(&displays)?);
+        Ok(self)
     }
 
     /// Gets the word.
     pub fn word(&self, word_id: usize) -> &str {
-        &self.words[word_id]
+        self.words.get(word_id)
+    }
+
+    /// Gets the display form of the word, or the word itself if not given.
+    pub fn display(&self, word_id: usize) -> &str {
+        self.displays
+            .as_ref()
+            .map_or_else(|| self.word(word_id), |displays| displays.get(word_id))
+    }
+
+    /// Gets the number of words.
+    pub fn num_words(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Gets the weight of the word, if given.
+    pub fn weight(&self, word_id: usize) -> Option<f64> {
+        self.weights
+            .as_ref()
+            .map(|weights| utils::read_f64(weights, word_id))
+    }
+
+    /// Gets the maximum weight, if given.
+    pub(crate) fn max_weight(&self) -> Option<f64> {
+        self.weights.as_ref().map(|weights| {
+            (0..self.num_words()).fold(0.0, |max, i| utils::read_f64(weights, i).max(max))
+        })
     }
 
     /// Gets the reference of the trie.
-    pub const fn trie(&self) -> &Trie {
+    pub const fn trie(&self) -> &Trie<'a> {
         &self.trie
     }
+
+    /// Serializes the lexicon into the writer.
+    ///
+    /// The format consists of the following little-endian values:
+    ///
+    ///  - magic `b"GNLX"` and version (`u32`),
+    ///  - number of trie units (`u64`) and the units (`u32` each),
+    ///  - number of words (`u64`), start offsets of words and end offset of the last one (`u32` each),
+    ///    and the concatenated words,
+    ///  - flag of weights (`u8`) and the weights (`f64` each) if the flag is 1,
+    ///  - flag of display forms (`u8`) and, if the flag is 1, their offsets and concatenation
+    ///    in the same manner as words.
+    pub fn serialize_into<W>(&self, mut wtr: W) -> Result<()>
+    where
+        W: Write,
+    {
+        wtr.write_all(MAGIC)?;
+        wtr.write_all(&VERSION.to_le_bytes())?;
+        let data = self.trie.data();
+        wtr.write_all(&(data.len() as u64 / 4).to_le_bytes())?;
+        wtr.write_all(data)?;
+        wtr.write_all(&(self.num_words() as u64).to_le_bytes())?;
+        self.words.serialize_into(&mut wtr)?;
+        if let Some(weights) = &self.weights {
+            wtr.write_all(&[1])?;
+            wtr.write_all(weights)?;
+        } else {
+            wtr.write_all(&[0])?;
+        }
+        if let Some(displays) = &self.displays {
+            wtr.write_all(&[1])?;
+            displays.serialize_into(&mut wtr)?;
+        } else {
+            wtr.write_all(&[0])?;
+        }
+        Ok(())
+    }
+
+    /// Deserializes the lexicon from the reader, written by [`Lexicon::serialize_into`].
+    pub fn deserialize_from<R>(mut rdr: R) -> Result<Lexicon<'static>>
+    where
+        R: Read,
+    {
+        let mut bytes = vec![];
+        rdr.read_to_end(&mut bytes)?;
+        Ok(Lexicon::from_bytes(&bytes)?.into_owned())
+    }
+
+    /// Creates an instance borrowing the bytes written by [`Lexicon::serialize_into`]
+    /// without copying, e.g., from a memory-mapped file or `include_bytes!`.
+    pub fn from_bytes(mut bytes: &'a [u8]) -> Result<Self> {
+        if take_bytes(&mut bytes, 4)? != MAGIC {
+            return Err(anyhow!("The input is not a serialized lexicon."));
+        }
+        let version = utils::read_u32(take_bytes(&mut bytes, 4)?, 0);
+        if version != VERSION {
+            return Err(anyhow!(
+                "The lexicon version {} is not supported (expected {}).",
+                version,
+                VERSION
+            ));
+        }
+        let num_units = take_len(&mut bytes)?;
+        let data = take_bytes(&mut bytes, num_units.saturating_mul(4))?;
+        let num_words = take_len(&mut bytes)?;
+        let words = Strings::from_bytes(&mut bytes, num_words)?;
+        let weights = match take_bytes(&mut bytes, 1)? {
+            [0] => None,
+            [1] => Some(take_bytes(&mut bytes, num_words.saturating_mul(8))?),
+            _ => return Err(anyhow!("The flag of weights is broken.")),
+        };
+        let displays = match take_bytes(&mut bytes, 1)? {
+            [0] => None,
+            [1] => Some(Strings::from_bytes(&mut bytes, num_words)?),
+            _ => return Err(anyhow!("The flag of display forms is broken.")),
+        };
+        if !bytes.is_empty() {
+            return Err(anyhow!("The input has trailing bytes."));
+        }
+        Ok(Self {
+            words,
+            weights: weights.map(Cow::Borrowed),
+            displays,
+            trie: Trie::from_data(Cow::Borrowed(data), num_words)?,
+        })
+    }
+
+    /// Converts into an instance owning the data.
+    pub fn into_owned(self) -> Lexicon<'static> {
+        Lexicon {
+            words: self.words.into_owned(),
+            weights: self.weights.map(|weights| Cow::Owned(weights.into_owned())),
+            displays: self.displays.map(Strings::into_owned),
+            trie: self.trie.into_owned(),
+        }
+    }
+}
+
+// Array of strings in the serialized layout.
+#[derive(PartialEq, Eq, Debug)]
+struct Strings<'a> {
+    // Concatenated strings.
+    concat: Cow<'a, str>,
+    // Start offsets of strings and end offset of the last one (little-endian u32 each).
+    offsets: Cow<'a, [u8]>,
+}
+
+impl<'a> Strings<'a> {
+    fn new<S>(strs: &[S]) -> Result<Self>
+    where
+        S: AsRef<str>,
+    {
+        let mut concat = String::new();
+        let mut offsets = Vec::with_capacity((strs.len() + 1) * 4);
+        offsets.extend_from_slice(&0u32.to_le_bytes());
+        for s in strs {
+            concat.push_str(s.as_ref());
+            let offset = u32::try_from(concat.len())
+                .map_err(|_| anyhow!("The total length of words must fit in u32."))?;
+            offsets.extend_from_slice(&offset.to_le_bytes());
+        }
+        Ok(Self {
+            concat: Cow::Owned(concat),
+            offsets: Cow::Owned(offsets),
+        })
+    }
+
+    fn from_bytes(bytes: &mut &'a [u8], len: usize) -> Result<Self> {
+        let offsets = take_bytes(bytes, len.saturating_add(1).saturating_mul(4))?;
+        let num_bytes = utils::read_u32(offsets, len) as usize;
+        let concat = std::str::from_utf8(take_bytes(bytes, num_bytes)?)?;
+        let mut start = 0;
+        for i in 0..=len {
+            let end = utils::read_u32(offsets, i) as usize;
+            if end < start || !concat.is_char_boundary(end) {
+                return Err(anyhow!("The word offsets are broken."));
+            }
+            start = end;
+        }
+        Ok(Self {
+            concat: Cow::Borrowed(concat),
+            offsets: Cow::Borrowed(offsets),
+        })
+    }
+
+    fn serialize_into<W>(&self, mut wtr: W) -> Result<()>
+    where
+        W: Write,
+    {
+        wtr.write_all(&self.offsets)?;
+        wtr.write_all(self.concat.as_bytes())?;
+        Ok(())
+    }
+
+    fn into_owned(self) -> Strings<'static> {
+        Strings {
+            concat: Cow::Owned(self.concat.into_owned()),
+            offsets: Cow::Owned(self.offsets.into_owned()),
+        }
+    }
+
+    fn get(&self, i: usize) -> &str {
+        let start = utils::read_u32(&self.offsets, i) as usize;
+        let end = utils::read_u32(&self.offsets, i + 1) as usize;
+        &self.concat[start..end]
+    }
+
+    fn len(&self) -> usize {
+        self.offsets.len() / 4 - 1
+    }
+}
+
+fn take_bytes<'a>(bytes: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+    if bytes.len() < len {
+        return Err(anyhow!("The input is truncated."));
+    }
+    let (head, tail) = bytes.split_at(len);
+    *bytes = tail;
+    Ok(head)
+}
+
+fn take_len(bytes: &mut &[u8]) -> Result<usize> {
+    let len = u64::from_le_bytes(take_bytes(bytes, 8)?.try_into()?);
+    Ok(usize::try_from(len)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_lexicon_eq(lex: &Lexicon, other: &Lexicon) {
+        assert_eq!(lex.words, other.words);
+        assert_eq!(lex.weights, other.weights);
+        assert_eq!(lex.displays, other.displays);
+        assert_eq!(lex.trie.data(), other.trie.data());
+    }
+
+    #[test]
+    fn test_weights() {
+        let lex = Lexicon::with_weights([("aa", 1.0), ("ab", 3.0), ("b", 0.0)]).unwrap();
+        assert_eq!(lex.weight(0), Some(1.0));
+        assert_eq!(lex.weight(1), Some(3.0));
+        assert_eq!(lex.weight(2), Some(0.0));
+        assert_eq!(lex.max_weight(), Some(3.0));
+
+        let lex = Lexicon::new(["aa", "ab", "b"]).unwrap();
+        assert_eq!(lex.weight(0), None);
+        assert_eq!(lex.max_weight(), None);
+    }
+
+    #[test]
+    fn test_display_forms() {
+        let lex = Lexicon::new(["github", "iphone", "word"]).unwrap();
+        assert_eq!(lex.display(0), "github");
+        let lex = lex.display_forms(["GitHub", "iPhone", "word"]).unwrap();
+        assert_eq!(lex.word(0), "github");
+        assert_eq!(lex.display(0), "GitHub");
+        assert_eq!(lex.display(1), "iPhone");
+        assert_eq!(lex.display(2), "word");
+
+        let lex = Lexicon::new(["github", "iphone"]).unwrap();
+        assert!(lex.display_forms(["GitHub"]).is_err());
+        let lex = Lexicon::new(["github", "iphone"]).unwrap();
+        assert!(lex.display_forms(["GitHub", ""]).is_err());
+    }
+
+    #[test]
+    fn test_serialize() {
+        let words = &["aa", "abaab", "abb", "bab", "bb", "bbb", "cbab", "ccbab"];
+        let lex = Lexicon::new(words).unwrap();
+        let mut bytes = vec![];
+        lex.serialize_into(&mut bytes).unwrap();
+        let other = Lexicon::deserialize_from(&bytes[..]).unwrap();
+        assert_lexicon_eq(&lex, &other);
+        assert_eq!(other.num_words(), words.len());
+        for (i, &word) in words.iter().enumerate() {
+            assert_eq!(other.word(i), word);
+        }
+
+        let lex = Lexicon::with_weights([("aa", 1.0), ("ab", 3.0), ("b", 0.0)])
+            .unwrap()
+            .display_forms(["AA", "Ab", "b"])
+            .unwrap();
+        let mut bytes = vec![];
+        lex.serialize_into(&mut bytes).unwrap();
+        let other = Lexicon::deserialize_from(&bytes[..]).unwrap();
+        assert_lexicon_eq(&lex, &other);
+        assert_eq!(other.display(1), "Ab");
+    }
+
+    #[test]
+    fn test_from_bytes() {
+        let lex = Lexicon::with_weights([("aa", 1.0), ("ab", 3.0), ("b", 0.0)]).unwrap();
+        let mut bytes = vec![];
+        lex.serialize_into(&mut bytes).unwrap();
+        let other = Lexicon::from_bytes(&bytes).unwrap();
+        assert!(matches!(other.words.concat, Cow::Borrowed(_)));
+        assert!(matches!(other.weights, Some(Cow::Borrowed(_))));
+        assert_lexicon_eq(&lex, &other);
+        assert_lexicon_eq(&lex, &other.into_owned());
+    }
+
+    #[test]
+    fn test_deserialize_invalid() {
+        let lex = Lexicon::new(["aa", "ab", "b"]).unwrap();
+        let mut bytes = vec![];
+        lex.serialize_into(&mut bytes).unwrap();
+        assert!(Lexicon::deserialize_from(&bytes[..bytes.len() - 1]).is_err());
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(Lexicon::from_bytes(&trailing).is_err());
+
+        // The trie units are checked not to panic in searching.
+        let lex = Lexicon::new(["aa", "ab", "b"]).unwrap();
+        let data = lex.trie.data();
+        let num_units = data.len() / 4;
+        let mut broken = vec![];
+        broken.extend_from_slice(MAGIC);
+        broken.extend_from_slice(&VERSION.to_le_bytes());
+        broken.extend_from_slice(&1u64.to_le_bytes());
+        broken.extend_from_slice(&data[..4]);
+        broken.extend_from_slice(&bytes[16 + data.len()..]);
+        let other = Lexicon::from_bytes(&broken).unwrap();
+        let enumerator = crate::Enumerator::new(&other, "ab").unwrap();
+        assert!(enumerator.all_subsequences().unwrap().matches.is_empty());
+        let leaf = (0..num_units)
+            .find(|&i| utils::read_u32(data, i) >> 31 == 1)
+            .unwrap();
+        let mut broken = bytes.clone();
+        broken[16 + leaf * 4..16 + leaf * 4 + 4].copy_from_slice(&(1u32 << 31 | 3).to_le_bytes());
+        assert!(Lexicon::from_bytes(&broken).is_err());
+        assert!(Lexicon::deserialize_from(&broken[..]).is_err());
+
+        bytes[4] += 1; // version
+        assert!(Lexicon::deserialize_from(&bytes[..]).is_err());
+        bytes[0] = b'X'; // magic
+        assert!(Lexicon::deserialize_from(&bytes[..]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_weights() {
+        assert!(Lexicon::with_weights([("aa", 1.0), ("ab", -1.0)]).is_err());
+        assert!(Lexicon::with_weights([("aa", 1.0), ("ab", f64::NAN)]).is_err());
+    }
 }
@@ -14,7 +14,7 @@
 //! let text = "abAaB";
 //!
 //! let enumerator = Enumerator::new(&lex, text).unwrap().prefix_len(2).unwrap();
-//! let matched = enumerator.all_subsequences().unwrap();
+//! let matched = enumerator.all_subsequences().unwrap().matches;
 //!
 //! assert_eq!(matched.len(), 4);
 //! assert_eq!(
@@ -34,10 +34,24 @@
 //!     ("CCbab".to_string(), "aBAaB".to_string())
 //! );
 //! ```
+mod builder;
+mod denylist;
 mod enumerator;
 mod lexicon;
+mod pronounceability;
+mod registry;
+mod scorer;
+mod stopwords;
+mod tokenizer;
 mod trie;
 mod utils;
 
-pub use enumerator::{Enumerator, Match};
+pub use builder::{BuildReport, InvalidPolicy, LexiconBuilder};
+pub use denylist::Denylist;
+pub use enumerator::{Constraint, Enumerator, LimitPolicy, Match, Matches, Subsequences};
 pub use lexicon::Lexicon;
+pub use pronounceability::Pronounceability;
+pub use registry::{Availability, Conflict, Registry};
+pub use scorer::{Candidate, CoverageScorer, PositionalScorer, Scorer};
+pub use stopwords::Stopwords;
+pub use tokenizer::Tokenizer;
@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use crate::Lexicon;
+
+// Markers of the beginning and end of a word.
+const BOW: u8 = b'^';
+const EOW: u8 = b'$';
+
+/// Pronounceability estimator based on a character trigram model.
+///
+/// The pronounceability of a word is the geometric mean of the probabilities of its letters
+/// (including the end of the word) conditioned on the two preceding ones, estimated with add-one smoothing.
+/// It is in `(0, 1]`, and the higher the more pronounceable.
+pub struct Pronounceability {
+    trigrams: HashMap<[u8; 3], u32>,
+    bigrams: HashMap<[u8; 2], u32>,
+    alphabet_size: usize,
+}
+
+impl Pronounceability {
+    /// Trains the model from the words in the lexicon.
+    pub fn new(lex: &Lexicon<'_>) -> Self {
+        Self::from_words((0..lex.num_words()).map(|word_id| lex.word(word_id)))
+    }
+
+    /// Trains the model from a set of words.
+    pub fn from_words<I, W>(words: I) -> Self
+    where
+        I: IntoIterator<Item = W>,
+        W: AsRef<str>,
+    {
+        let mut trigrams = HashMap::new();
+        let mut bigrams = HashMap::new();
+        let mut alphabet = [false; 256];
+        alphabet[EOW as usize] = true;
+        for word in words {
+            for gram in Self::padded(word.as_ref()).windows(3) {
+                alphabet[gram[2] as usize] = true;
+                *trigrams.entry([gram[0], gram[1], gram[2]]).or_insert(0) += 1;
+                *bigrams.entry([gram[0], gram[1]]).or_insert(0) += 1;
+            }
+        }
+        let alphabet_size = alphabet.iter().filter(|&&b| b).count();
+        Self {
+            trigrams,
+            bigrams,
+            alphabet_size,
+        }
+    }
+
+    /// Estimates the pronounceability of the word.
+    pub fn score(&self, word: &str) -> f64 {
+        let padded = Self::padded(word);
+        let mut log_prob = 0.0;
+        for gram in padded.windows(3) {
+            let trigram = self.trigrams.get(&[gram[0], gram[1], gram[2]]);
+            let bigram = self.bigrams.get(&[gram[0], gram[1]]);
+            let numer = f64::from(trigram.copied().unwrap_or(0) + 1);
+            let denom = f64::from(bigram.copied().unwrap_or(0)) + self.alphabet_size as f64;
+            log_prob += (numer / denom).ln();
+        }
+        (log_prob / (padded.len() - 2) as f64).exp()
+    }
+
+    fn padded(word: &str) -> Vec<u8> {
+        let mut padded = Vec::with_capacity(word.len() + 3);
+        padded.extend_from_slice(&[BOW, BOW]);
+        padded.extend_from_slice(word.as_bytes());
+        padded.push(EOW);
+        padded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pronounceability() {
+        let words = &["banana", "bandana", "cabana", "canal", "panama"];
+        let model = Pronounceability::from_words(words);
+        assert!(model.score("ban") > model.score("bnn"));
+        assert!(model.score("nana") > model.score("aann"));
+        for word in ["", "a", "xyz", "banana"] {
+            let score = model.score(word);
+            assert!(0.0 < score && score <= 1.0);
+        }
+    }
+}
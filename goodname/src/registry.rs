@@ -0,0 +1,201 @@
+use std::collections::{BTreeSet, HashSet};
+
+use anyhow::Result;
+
+use crate::trie::Trie;
+
+/// Availability of a name in a [`Registry`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Availability {
+    /// The name is registered as it is (ignoring letter case).
+    Taken,
+    /// A name identical except for letter case and separators such as `-` and `_` is registered
+    /// (e.g., `foo-bar` for `foobar`).
+    Similar,
+    /// No such names are registered.
+    Free,
+}
+
+/// Registered name close to a candidate, found by [`Registry::nearest`].
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct Conflict {
+    /// The registered name (in lower case).
+    pub name: String,
+    /// The Levenshtein distance from the candidate.
+    pub distance: usize,
+}
+
+/// Snapshot of names already taken, such as a package index of crates.io, npm, or PyPI.
+///
+/// The names are stored in a trie, which is traversed to find names within a small edit distance.
+#[derive(Default)]
+pub struct Registry {
+    // Sorted lower-case names, identified in the trie.
+    names: Vec<String>,
+    trie: Option<Trie<'static>>,
+    // Letters in the names, to enumerate the children in the trie.
+    alphabet: Vec<char>,
+    keys: HashSet<String>,
+}
+
+impl Registry {
+    /// Creates an instance from a set of names,
+    /// skipping empty ones and comments starting with `#`, such as lines of a file.
+    pub fn new<I, W>(names: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = W>,
+        W: AsRef<str>,
+    {
+        let mut sorted = BTreeSet::new();
+        let mut keys = HashSet::new();
+        for name in names {
+            let name = name.as_ref().trim();
+            if name.is_empty() || name.starts_with('#') {
+                continue;
+            }
+            keys.insert(Self::key(name));
+            sorted.insert(name.to_lowercase());
+        }
+        let names: Vec<_> = sorted.into_iter().collect();
+        let trie = if names.is_empty() {
+            None
+        } else {
+            Some(Trie::from_words(&names)?)
+        };
+        let alphabet: BTreeSet<_> = names.iter().flat_map(|name| name.chars()).collect();
+        Ok(Self {
+            names,
+            trie,
+            alphabet: alphabet.into_iter().collect(),
+            keys,
+        })
+    }
+
+    /// Gets the number of names.
+    pub const fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Checks if there are no names.
+    pub const fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Checks the availability of the name.
+    pub fn check(&self, name: &str) -> Availability {
+        let name = name.to_lowercase();
+        let taken = self.trie.as_ref().is_some_and(|trie| {
+            trie.get_descendant(Trie::root_pos(), name.as_bytes())
+                .and_then(|node_pos| trie.get_value(node_pos))
+                .is_some()
+        });
+        if taken {
+            Availability::Taken
+        } else if self.keys.contains(&Self::key(&name)) {
+            Availability::Similar
+        } else {
+            Availability::Free
+        }
+    }
+
+    /// Finds at most `max_names` registered names within Levenshtein distance `max_distance`
+    /// from the name, in the order of distance and then name.
+    pub fn nearest(&self, name: &str, max_distance: usize, max_names: usize) -> Vec<Conflict> {
+        let trie = match &self.trie {
+            Some(trie) => trie,
+            None => return vec![],
+        };
+        let query: Vec<_> = name.to_lowercase().chars().collect();
+        let mut conflicts = vec![];
+        // Each node has the row of the edit distances between its prefix and those of the query.
+        let mut stack = vec![(Trie::root_pos(), (0..=query.len()).collect::<Vec<_>>())];
+        let mut buf = [0; 4];
+        while let Some((node_pos, row)) = stack.pop() {
+            for &c in &self.alphabet {
+                let child_pos =
+                    match trie.get_descendant(node_pos, c.encode_utf8(&mut buf).as_bytes()) {
+                        Some(child_pos) => child_pos,
+                        None => continue,
+                    };
+                let mut next = Vec::with_capacity(row.len());
+                next.push(row[0] + 1);
+                for (j, &q) in query.iter().enumerate() {
+                    let cost = usize::from(q != c);
+                    next.push((row[j + 1] + 1).min(next[j] + 1).min(row[j] + cost));
+                }
+                let distance = next[query.len()];
+                if distance <= max_distance {
+                    if let Some(name_id) = trie.get_value(child_pos) {
+                        conflicts.push(Conflict {
+                            name: self.names[name_id].clone(),
+                            distance,
+                        });
+                    }
+                }
+                if next.iter().min().is_some_and(|&d| d <= max_distance) {
+                    stack.push((child_pos, next));
+                }
+            }
+        }
+        conflicts.sort_by(|c1, c2| {
+            c1.distance
+                .cmp(&c2.distance)
+                .then_with(|| c1.name.cmp(&c2.name))
+        });
+        conflicts.truncate(max_names);
+        conflicts
+    }
+
+    /// Folds the name into the key to find similar ones, keeping only lower-case alphanumerics.
+    fn key(name: &str) -> String {
+        name.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry() {
+        let registry =
+            Registry::new(["# crates", "serde", "Tokio", "", "foo-bar", "baz_qux"]).unwrap();
+        assert_eq!(registry.len(), 4);
+        assert_eq!(registry.check("serde"), Availability::Taken);
+        assert_eq!(registry.check("tokio"), Availability::Taken);
+        assert_eq!(registry.check("foobar"), Availability::Similar);
+        assert_eq!(registry.check("foo_bar"), Availability::Similar);
+        assert_eq!(registry.check("bazqux"), Availability::Similar);
+        assert_eq!(registry.check("serdes"), Availability::Free);
+        assert_eq!(registry.check("crates"), Availability::Free);
+        assert!(Registry::new([""]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_nearest() {
+        let registry =
+            Registry::new(["crawdad", "crawdads", "cheddar", "craw-dad", "yada"]).unwrap();
+        let conflict = |name: &str, distance| Conflict {
+            name: name.to_string(),
+            distance,
+        };
+        assert_eq!(
+            registry.nearest("crawdad", 1, 10),
+            vec![
+                conflict("crawdad", 0),
+                conflict("craw-dad", 1),
+                conflict("crawdads", 1)
+            ]
+        );
+        assert_eq!(
+            registry.nearest("crowdad", 1, 1),
+            vec![conflict("crawdad", 1)]
+        );
+        assert_eq!(registry.nearest("yoda", 1, 10), vec![conflict("yada", 1)]);
+        assert!(registry.nearest("yoda", 0, 10).is_empty());
+        assert!(Registry::default().nearest("yoda", 2, 10).is_empty());
+    }
+}
@@ -0,0 +1,243 @@
+use std::ops::Range;
+
+use anyhow::{anyhow, Result};
+
+use crate::Match;
+
+// Keeps each position score within 48 bits, leaving room for the boosts and the sums,
+// whose overflows are checked by the enumerator.
+const MAX_WORD_LEN: usize = 48;
+
+/// Information on a name candidate passed to [`Scorer::candidate_score`].
+pub struct Candidate<'a> {
+    /// The candidate word.
+    pub word: &'a str,
+    /// The input text.
+    pub text: &'a str,
+    /// The ranges of words in the input text, except for the stopwords.
+    pub segments: &'a [Range<usize>],
+    /// The match whose score is the sum of the position scores.
+    pub matched: &'a Match,
+}
+
+/// Scoring scheme of name candidates.
+///
+/// The score of a candidate is computed in two steps.
+/// First, the scores of the active positions given by [`Scorer::position_scores`] are summed up.
+/// Then, the sum is adjusted by [`Scorer::candidate_score`].
+pub trait Scorer {
+    /// Computes the score of each position (i.e., byte offset) of an input text,
+    /// given the ranges of words in the text.
+    /// Only the positions at which characters start can be active.
+    fn position_scores(&self, text: &str, segments: &[Range<usize>]) -> Result<Vec<u64>>;
+
+    /// Computes the final score of a candidate, defaulting to the sum of the position scores.
+    ///
+    /// The result must be no more than [`Scorer::max_candidate_score`] of the sum;
+    /// otherwise, [`Enumerator::top_k`](crate::Enumerator::top_k) may miss some candidates.
+    fn candidate_score(&self, cand: &Candidate) -> u64 {
+        cand.matched.score
+    }
+
+    /// Returns the upper bound of [`Scorer::candidate_score`]
+    /// for candidates whose sums of the position scores are no more than `score`.
+    fn max_candidate_score(&self, score: u64) -> u64 {
+        score
+    }
+}
+
+/// The default scoring scheme.
+///
+/// Each letter scores `2^(l - d)`, where `l` is the maximum number of letters in a word of the text
+/// and `d` is the number of letters from the beginning of its word to the letter.
+/// That is, the more forward letters of each word and the more letters matched, the higher the score.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct PositionalScorer;
+
+impl Scorer for PositionalScorer {
+    fn position_scores(&self, text: &str, segments: &[Range<usize>]) -> Result<Vec<u64>> {
+        let mut scores = vec![0; text.len()];
+        let max_word_len = segments
+            .iter()
+            .fold(0, |max, seg| max.max(text[seg.clone()].chars().count()));
+        if max_word_len == 0 {
+            return Ok(scores);
+        }
+        if MAX_WORD_LEN < max_word_len {
+            return Err(anyhow!(
+                "the length of each word in an input text must be no more than {}.",
+                MAX_WORD_LEN
+            ));
+        }
+        let max_score: u64 = 1 << (max_word_len - 1);
+        for seg in segments {
+            for (d, (i, _)) in text[seg.clone()].char_indices().enumerate() {
+                scores[seg.start + i] = max_score >> d;
+            }
+        }
+        Ok(scores)
+    }
+}
+
+/// Scoring scheme rewarding candidates that cover many words of the text.
+///
+/// The score of a candidate is the sum of
+///
+///  - `letter_score` for each matched letter,
+///  - `initial_score` for each matched letter at the beginning of a word, and
+///  - `word_score` for each word containing at least one matched letter,
+///
+/// minus `run_penalty` for each matched letter beyond the first `max_run` ones in a word, if specified.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CoverageScorer {
+    letter_score: u64,
+    initial_score: u64,
+    word_score: u64,
+    run_penalty: Option<(usize, u64)>,
+}
+
+impl Default for CoverageScorer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CoverageScorer {
+    /// Creates an instance with `letter_score = 1`, `initial_score = 4`, `word_score = 8`,
+    /// and no run penalty.
+    pub const fn new() -> Self {
+        Self {
+            letter_score: 1,
+            initial_score: 4,
+            word_score: 8,
+            run_penalty: None,
+        }
+    }
+
+    /// Specifies the score for each matched letter, which must be positive.
+    pub fn letter_score(mut self, letter_score: u64) -> Result<Self> {
+        if letter_score == 0 {
+            return Err(anyhow!("the letter score must be positive."));
+        }
+        self.letter_score = letter_score;
+        Ok(self)
+    }
+
+    /// Specifies the additional score for each matched letter at the beginning of a word.
+    pub const fn initial_score(mut self, initial_score: u64) -> Self {
+        self.initial_score = initial_score;
+        self
+    }
+
+    /// Specifies the additional score for each covered word.
+    pub const fn word_score(mut self, word_score: u64) -> Self {
+        self.word_score = word_score;
+        self
+    }
+
+    /// Penalizes each matched letter beyond the first `max_run` ones in a word by `penalty`.
+    pub const fn run_penalty(mut self, max_run: usize, penalty: u64) -> Self {
+        self.run_penalty = Some((max_run, penalty));
+        self
+    }
+}
+
+impl Scorer for CoverageScorer {
+    fn position_scores(&self, text: &str, segments: &[Range<usize>]) -> Result<Vec<u64>> {
+        let mut scores = vec![0; text.len()];
+        for seg in segments {
+            for (i, _) in text[seg.clone()].char_indices() {
+                scores[seg.start + i] = self.letter_score;
+            }
+            scores[seg.start] = scores[seg.start].saturating_add(self.initial_score);
+        }
+        Ok(scores)
+    }
+
+    fn candidate_score(&self, cand: &Candidate) -> u64 {
+        let mut score = cand.matched.score;
+        let mut positions = cand.matched.positions.iter().peekable();
+        for seg in cand.segments {
+            while positions.next_if(|&&i| i < seg.start).is_some() {}
+            let mut num_letters = 0;
+            while positions.next_if(|&&i| i < seg.end).is_some() {
+                num_letters += 1;
+            }
+            if num_letters == 0 {
+                continue;
+            }
+            score = score.saturating_add(self.word_score);
+            if let Some((max_run, penalty)) = self.run_penalty {
+                let excess = (num_letters - num_letters.min(max_run)) as u64;
+                score = score.saturating_sub(excess.saturating_mul(penalty));
+            }
+        }
+        score
+    }
+
+    fn max_candidate_score(&self, score: u64) -> u64 {
+        // Each covered word has at least one letter scoring no less than letter_score.
+        score.saturating_add(self.word_score.saturating_mul(score / self.letter_score))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_positional_scores() {
+        let scores = PositionalScorer
+            .position_scores("ab abc a", &[0..2, 3..6, 7..8])
+            .unwrap();
+        assert_eq!(scores, vec![4, 2, 0, 4, 2, 1, 0, 4]);
+    }
+
+    #[test]
+    fn test_positional_scores_multibyte() {
+        let scores = PositionalScorer
+            .position_scores("éa bé", &[0..3, 4..7])
+            .unwrap();
+        assert_eq!(scores, vec![2, 0, 1, 0, 2, 1, 0]);
+    }
+
+    #[test]
+    fn test_positional_scores_long_word() {
+        let text = "b".repeat(MAX_WORD_LEN + 1);
+        let seg = 0..text.len();
+        assert!(PositionalScorer.position_scores(&text, &[seg]).is_err());
+    }
+
+    #[test]
+    fn test_coverage_scores() {
+        let text = "ab abc a";
+        let segments = &[0..2, 3..6, 7..8];
+        let scorer = CoverageScorer::new().run_penalty(1, 3);
+        let scores = scorer.position_scores(text, segments).unwrap();
+        assert_eq!(scores, vec![5, 1, 0, 5, 1, 1, 0, 5]);
+
+        let mut matched = Match {
+            word_id: 0,
+            lexicon_id: 0,
+            score: 0,
+            positions: vec![0, 3, 4, 5],
+            prefix: "".to_string(),
+            suffix: "".to_string(),
+            insertions: vec![],
+            order: vec![],
+            pronounceability: None,
+            availability: None,
+            conflicts: vec![],
+        };
+        matched.score = matched.positions.iter().map(|&i| scores[i]).sum();
+        let cand = Candidate {
+            word: "aabc",
+            text,
+            segments,
+            matched: &matched,
+        };
+        // 12 + 8 * 2 - 3 * 2
+        assert_eq!(scorer.candidate_score(&cand), 22);
+        assert!(scorer.candidate_score(&cand) <= scorer.max_candidate_score(matched.score));
+    }
+}
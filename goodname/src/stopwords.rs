@@ -0,0 +1,85 @@
+use std::collections::HashSet;
+
+use unicode_normalization::UnicodeNormalization;
+
+// Common English function words, such as articles, prepositions, conjunctions, and pronouns.
+const ENGLISH: &[&str] = &[
+    "a", "about", "after", "against", "all", "an", "and", "any", "are", "as", "at", "be", "been",
+    "before", "between", "both", "but", "by", "can", "do", "does", "each", "for", "from", "has",
+    "have", "how", "i", "if", "in", "into", "is", "it", "its", "my", "no", "nor", "not", "of",
+    "off", "on", "onto", "or", "our", "out", "over", "own", "per", "so", "such", "than", "that",
+    "the", "their", "them", "then", "there", "these", "they", "this", "those", "through", "to",
+    "under", "up", "upon", "us", "via", "was", "we", "were", "what", "when", "where", "which",
+    "while", "who", "whom", "why", "will", "with", "within", "without", "you", "your",
+];
+
+/// Set of words to ignore in texts, such as articles and prepositions.
+///
+/// Given to [`Enumerator::stopwords`](crate::Enumerator::stopwords), the stopwords in the text
+/// are skipped without contributing letters, and do not affect the scores of the other words.
+/// Entries are matched case-insensitively.
+#[derive(Clone, Debug, Default)]
+pub struct Stopwords {
+    words: HashSet<String>,
+}
+
+impl Stopwords {
+    /// Creates an instance from a set of words,
+    /// skipping empty ones and comments starting with `#`, such as lines of a file.
+    pub fn new<I, W>(words: I) -> Self
+    where
+        I: IntoIterator<Item = W>,
+        W: AsRef<str>,
+    {
+        let words = words
+            .into_iter()
+            .map(|word| word.as_ref().trim().to_lowercase())
+            .filter(|word| !word.is_empty() && !word.starts_with('#'))
+            .map(|word| word.nfc().collect())
+            .collect();
+        Self { words }
+    }
+
+    /// Creates an instance of the default English stopwords, such as `a`, `the`, and `for`.
+    pub fn english() -> Self {
+        Self::new(ENGLISH)
+    }
+
+    /// Gets the number of words.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Checks if there are no words.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Checks if the word is a stopword.
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(&word.to_lowercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stopwords() {
+        let stopwords = Stopwords::new(["# comment", "", "The", "of "]);
+        assert_eq!(stopwords.len(), 2);
+        assert!(stopwords.contains("the"));
+        assert!(stopwords.contains("The"));
+        assert!(stopwords.contains("of"));
+        assert!(!stopwords.contains("for"));
+        assert!(!stopwords.contains("# comment"));
+        assert!(Stopwords::default().is_empty());
+
+        let english = Stopwords::english();
+        assert_eq!(english.len(), ENGLISH.len());
+        assert!(english.contains("a"));
+        assert!(english.contains("for"));
+        assert!(!english.contains("naming"));
+    }
+}
@@ -0,0 +1,110 @@
+use std::ops::Range;
+
+use unicode_normalization::char::is_combining_mark;
+
+/// Tokenizer splitting a text into words, given to [`Enumerator::with_tokenizer`](crate::Enumerator::with_tokenizer).
+///
+/// Words are runs of letters, and the other characters such as spaces, hyphens, underscores,
+/// slashes, punctuation, and digits separate them (e.g., `character-wise double_array`
+/// into `character`, `wise`, `double`, and `array`). The separators are never matched.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Tokenizer {
+    camel_case: bool,
+}
+
+impl Tokenizer {
+    /// Creates an instance.
+    pub const fn new() -> Self {
+        Self { camel_case: false }
+    }
+
+    /// Specifies whether to split words at camelCase boundaries (default: `false`).
+    ///
+    /// If enabled, e.g., `doubleArrayTrie` and `XMLParser` are split into `double`, `Array`, and `Trie`,
+    /// and `XML` and `Parser`, respectively. The UPPERCASE letters starting such words are
+    /// not forced to be included in candidates, unlike the other UPPERCASE letters.
+    pub const fn camel_case(mut self, yes: bool) -> Self {
+        self.camel_case = yes;
+        self
+    }
+
+    /// Splits the text into the ranges of words,
+    /// where the words split at camelCase boundaries are adjacent to the preceding ones.
+    pub(crate) fn tokenize(&self, text: &str) -> Vec<Range<usize>> {
+        let chars: Vec<_> = text.chars().collect();
+        let mut segments = vec![];
+        let mut pos = 0;
+        let mut start = None;
+        for (i, &c) in chars.iter().enumerate() {
+            if !Self::is_letter(c) {
+                if let Some(start) = start.take() {
+                    segments.push(start..pos);
+                }
+            } else if start.is_none() {
+                start = Some(pos);
+            } else if self.camel_case && Self::is_camel_boundary(&chars, i) {
+                segments.push(start.unwrap()..pos);
+                start = Some(pos);
+            }
+            pos += c.len_utf8();
+        }
+        if let Some(start) = start {
+            segments.push(start..pos);
+        }
+        segments
+    }
+
+    fn is_letter(c: char) -> bool {
+        c.is_alphabetic() || is_combining_mark(c)
+    }
+
+    /// Checks if a word starts at `chars[i]`, which follows a letter.
+    fn is_camel_boundary(chars: &[char], i: usize) -> bool {
+        if !chars[i].is_uppercase() {
+            return false;
+        }
+        // `aB` or `ABCd`, e.g., `XMLParser` into `XML` and `Parser`,
+        // but not `ABc` such as `CHaracter` whose capitals are emphasized.
+        chars[i - 1].is_lowercase()
+            || (i >= 2
+                && chars[i - 2].is_uppercase()
+                && chars[i - 1].is_uppercase()
+                && chars.get(i + 1).is_some_and(|c| c.is_lowercase()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        let tokenizer = Tokenizer::new();
+        assert_eq!(tokenizer.tokenize("ab abc a"), vec![0..2, 3..6, 7..8]);
+        assert_eq!(tokenizer.tokenize(" ab  abc "), vec![1..3, 5..8]);
+        assert_eq!(tokenizer.tokenize(""), vec![]);
+        assert_eq!(
+            tokenizer.tokenize("char-wise double_array/trie, v2\tdict."),
+            vec![0..4, 5..9, 10..16, 17..22, 23..27, 29..30, 32..36]
+        );
+        assert_eq!(tokenizer.tokenize("éa bé"), vec![0..3, 4..7]);
+        assert_eq!(tokenizer.tokenize("doubleArray"), vec![0..11]);
+    }
+
+    #[test]
+    fn test_tokenize_camel_case() {
+        let tokenizer = Tokenizer::new().camel_case(true);
+        assert_eq!(
+            tokenizer.tokenize("doubleArrayTrie"),
+            vec![0..6, 6..11, 11..15]
+        );
+        assert_eq!(tokenizer.tokenize("XMLParser"), vec![0..3, 3..9]);
+        assert_eq!(
+            tokenizer.tokenize("Character wise-Double"),
+            vec![0..9, 10..14, 15..21]
+        );
+        assert_eq!(tokenizer.tokenize("CHaracterWise"), vec![0..9, 9..13]);
+        assert_eq!(tokenizer.tokenize("IOStream"), vec![0..2, 2..8]);
+        assert_eq!(tokenizer.tokenize("ÉtéÉclair"), vec![0..5, 5..12]);
+    }
+}
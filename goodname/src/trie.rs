@@ -1,12 +1,16 @@
+use std::borrow::Cow;
+
 use anyhow::{anyhow, Result};
+use unicode_normalization::is_nfc;
 
 use crate::utils;
 
-pub struct Trie {
-    units: Vec<u32>,
+pub struct Trie<'a> {
+    // Little-endian u32 units, which can be borrowed from serialized bytes.
+    data: Cow<'a, [u8]>,
 }
 
-impl Trie {
+impl<'a> Trie<'a> {
     pub fn from_words<W>(words: &[W]) -> Result<Self>
     where
         W: AsRef<str>,
@@ -20,11 +24,49 @@ impl Trie {
         let data = yada::builder::DoubleArrayBuilder::build(&records)
             .ok_or_else(|| anyhow!("Failed to run yada::builder::DoubleArrayBuilder::build."))?;
         assert_eq!(data.len() % 4, 0);
-        let mut units = Vec::with_capacity(data.len() / 4);
-        for i in (0..data.len()).step_by(4) {
-            units.push(u32::from_le_bytes(data[i..i + 4].try_into().unwrap()));
+        Ok(Self {
+            data: Cow::Owned(data),
+        })
+    }
+
+    /// Creates an instance from the units, whose values must be less than `num_values`.
+    pub(crate) fn from_data(data: Cow<'a, [u8]>, num_values: usize) -> Result<Self> {
+        if data.is_empty() || !data.len().is_multiple_of(4) {
+            return Err(anyhow!("The trie units are broken."));
+        }
+        let trie = Self { data };
+        trie.verify_values(num_values)?;
+        Ok(trie)
+    }
+
+    pub(crate) fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub(crate) fn into_owned(self) -> Trie<'static> {
+        Trie {
+            data: Cow::Owned(self.data.into_owned()),
+        }
+    }
+
+    /// Checks that every internal unit with a leaf points to a leaf unit with a valid value.
+    fn verify_values(&self, num_values: usize) -> Result<()> {
+        for node_pos in 0..self.data.len() / 4 {
+            let node_pos = u32::try_from(node_pos)?;
+            let unit = self.get_unit(node_pos).unwrap();
+            if Self::is_leaf(unit) || !Self::has_leaf(unit) {
+                continue;
+            }
+            let valid = self
+                .get_unit(Self::offset(unit) ^ node_pos)
+                .is_some_and(|leaf| {
+                    Self::is_leaf(leaf) && (Self::value(leaf) as usize) < num_values
+                });
+            if !valid {
+                return Err(anyhow!("The trie values are broken."));
+            }
         }
-        Ok(Self { units })
+        Ok(())
     }
 
     fn verify_words<W>(records: &[(W, u32)]) -> Result<()>
@@ -38,36 +80,34 @@ impl Trie {
         if a.is_empty() {
             return Err(anyhow!("Input words must not contain an empty one."));
         }
-        Self::verify_ascii(a)?;
+        Self::verify_letters(a)?;
         for i in 1..records.len() {
             let a = records[i - 1].0.as_ref();
             let b = records[i].0.as_ref();
             if a >= b {
                 return Err(anyhow!("Input words must be sorted ({} vs {}).", a, b));
             }
-            Self::verify_ascii(b)?;
+            Self::verify_letters(b)?;
         }
         Ok(())
     }
 
-    fn verify_ascii<W>(word: W) -> Result<()>
+    fn verify_letters<W>(word: W) -> Result<()>
     where
         W: AsRef<str>,
     {
         let word = word.as_ref();
-        for &c in word.as_bytes() {
-            if c >= 0x80 {
-                return Err(anyhow!(
-                    "Input words must not contain multibyte characters ({}).",
-                    word
-                ));
-            }
-            if utils::is_upper_case(c) {
-                return Err(anyhow!(
-                    "Input words must not contain upper-case letters ({}).",
-                    word
-                ));
-            }
+        if word.chars().any(utils::is_upper_case) {
+            return Err(anyhow!(
+                "Input words must not contain upper-case letters ({}).",
+                word
+            ));
+        }
+        if !is_nfc(word) {
+            return Err(anyhow!(
+                "Input words must be normalized into NFC ({}).",
+                word
+            ));
         }
         Ok(())
     }
@@ -79,9 +119,10 @@ impl Trie {
 
     #[inline(always)]
     pub(crate) fn get_value(&self, node_pos: u32) -> Option<usize> {
-        if Self::has_leaf(self.get_unit(node_pos)) {
-            let node_pos = Self::offset(self.get_unit(node_pos)) ^ node_pos;
-            Some(Self::value(self.get_unit(node_pos)) as usize)
+        let unit = self.get_unit(node_pos)?;
+        if Self::has_leaf(unit) {
+            let node_pos = Self::offset(unit) ^ node_pos;
+            Some(Self::value(self.get_unit(node_pos)?) as usize)
         } else {
             None
         }
@@ -90,13 +131,30 @@ impl Trie {
     #[inline(always)]
     pub(crate) fn get_child(&self, node_pos: u32, c: u8) -> Option<u32> {
         let c = c as u32;
-        let node_pos = Self::offset(self.get_unit(node_pos)) ^ node_pos ^ c;
-        Some(node_pos).filter(|&i| Self::label(self.get_unit(i)) == c)
+        let node_pos = Self::offset(self.get_unit(node_pos)?) ^ node_pos ^ c;
+        Some(node_pos).filter(|&i| self.get_unit(i).is_some_and(|unit| Self::label(unit) == c))
     }
 
+    /// Follows the bytes of a (multibyte) letter from the node.
     #[inline(always)]
-    fn get_unit(&self, node_pos: u32) -> u32 {
-        self.units[node_pos as usize]
+    pub(crate) fn get_descendant(&self, mut node_pos: u32, bytes: &[u8]) -> Option<u32> {
+        for &c in bytes {
+            node_pos = self.get_child(node_pos, c)?;
+        }
+        Some(node_pos)
+    }
+
+    /// Gets the unit, or `None` if out of range, e.g., in broken data.
+    #[inline(always)]
+    fn get_unit(&self, node_pos: u32) -> Option<u32> {
+        let start = (node_pos as usize).checked_mul(4)?;
+        let bytes = self.data.get(start..start.checked_add(4)?)?;
+        Some(u32::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    #[inline(always)]
+    const fn is_leaf(unit: u32) -> bool {
+        (unit >> 31) == 1
     }
 
     #[inline(always)]
@@ -137,6 +195,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_broken_data() {
+        let words = &["aa", "abaab", "abb", "bab", "bb", "bbb"];
+        let data = Trie::from_words(words).unwrap().data().to_vec();
+        assert!(Trie::from_data(Cow::Borrowed(&data), words.len()).is_ok());
+        assert!(Trie::from_data(Cow::Borrowed(&data), words.len() - 1).is_err());
+        assert!(Trie::from_data(Cow::Borrowed(&data[..data.len() - 1]), words.len()).is_err());
+
+        // Out-of-range units are never followed.
+        let trie = Trie::from_data(Cow::Borrowed(&data[..4]), words.len()).unwrap();
+        for c in 0..=u8::MAX {
+            assert_eq!(trie.get_child(Trie::root_pos(), c), None);
+        }
+        assert_eq!(trie.get_value(Trie::root_pos()), None);
+        assert_eq!(trie.get_value(u32::MAX), None);
+        assert_eq!(trie.get_child(u32::MAX, b'a'), None);
+    }
+
     #[test]
     #[should_panic]
     fn test_empty_set() {
@@ -163,7 +239,25 @@ mod tests {
 
     #[test]
     #[should_panic]
-    fn test_multibyte() {
+    fn test_multibyte_uppercase() {
         Trie::from_words(&["a", "Ｂ", "c"]).unwrap();
     }
+
+    #[test]
+    #[should_panic]
+    fn test_not_nfc() {
+        Trie::from_words(&["e\u{301}te\u{301}"]).unwrap();
+    }
+
+    #[test]
+    fn test_multibyte() {
+        let words = &["straße", "équipe"];
+        let trie = Trie::from_words(words).unwrap();
+        for (i, &word) in words.iter().enumerate() {
+            let node_pos = trie
+                .get_descendant(Trie::root_pos(), word.as_bytes())
+                .unwrap();
+            assert_eq!(i, trie.get_value(node_pos).unwrap());
+        }
+    }
 }
@@ -1,75 +1,108 @@
-const UPPER_TO_LOWER: [u8; 256] = {
-    let mut map = [0; 256];
-    let mut c = b'A';
-    while c <= b'Z' {
-        map[c as usize] = c + (b'a' - b'A'); // To the lower one
-        c += 1;
-    }
-    map
-};
-
-const LOWER_TO_UPPER: [u8; 256] = {
-    let mut map = [0; 256];
-    let mut c = b'a';
-    while c <= b'z' {
-        map[c as usize] = c - (b'a' - b'A'); // To the lower one
-        c += 1;
-    }
-    map
-};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+pub const DELIMITER: u8 = b' ';
+
+// Ranges of Latin letters with diacritics.
+const ACCENTED_RANGES: [(char, char); 2] = [('\u{c0}', '\u{24f}'), ('\u{1e00}', '\u{1eff}')];
 
+/// Reads the i-th little-endian u32 value.
 #[inline(always)]
-pub const fn is_lower_case(c: u8) -> bool {
-    LOWER_TO_UPPER[c as usize] != 0
+pub fn read_u32(bytes: &[u8], i: usize) -> u32 {
+    u32::from_le_bytes(bytes[i * 4..i * 4 + 4].try_into().unwrap())
 }
 
+/// Reads the i-th little-endian f64 value.
 #[inline(always)]
-pub const fn is_upper_case(c: u8) -> bool {
-    UPPER_TO_LOWER[c as usize] != 0
+pub fn read_f64(bytes: &[u8], i: usize) -> f64 {
+    f64::from_le_bytes(bytes[i * 8..i * 8 + 8].try_into().unwrap())
 }
 
 #[inline(always)]
-pub const fn to_lower_case(c: u8) -> Option<u8> {
-    if is_upper_case(c) {
-        Some(UPPER_TO_LOWER[c as usize])
-    } else {
-        None
-    }
+pub const fn is_upper_case(c: char) -> bool {
+    c.is_uppercase()
 }
 
+/// Folds the letter into lower case, which can consist of several characters (e.g., `İ`).
 #[inline(always)]
-pub const fn to_upper_case(c: u8) -> Option<u8> {
-    if is_lower_case(c) {
-        Some(LOWER_TO_UPPER[c as usize])
-    } else {
-        None
+pub fn to_lower_case(c: char) -> String {
+    c.to_lowercase().collect()
+}
+
+/// Converts the letter into upper case, which can consist of several characters (e.g., `ß`).
+#[inline(always)]
+pub fn to_upper_case(c: char) -> String {
+    c.to_uppercase().collect()
+}
+
+/// Strips the diacritics from the letter (e.g., `é` into `e`), if it decomposes into a single letter.
+pub fn base_letter(c: char) -> char {
+    let mut base = c.nfd().filter(|&d| !is_combining_mark(d));
+    match (base.next(), base.next()) {
+        (Some(b), None) => b,
+        _ => c,
     }
 }
 
+/// Returns the lower-case Latin letters with diacritics whose base letter is `c`
+/// (e.g., `é`, `è`, and so on for `e`).
+pub fn accented_letters(c: char) -> &'static [char] {
+    static ACCENTED: OnceLock<HashMap<char, Vec<char>>> = OnceLock::new();
+    let accented = ACCENTED.get_or_init(|| {
+        let mut accented: HashMap<char, Vec<char>> = HashMap::new();
+        for (first, last) in ACCENTED_RANGES {
+            for a in (first..=last).filter(|&a| a.is_lowercase()) {
+                let b = base_letter(a);
+                if b != a {
+                    accented.entry(b).or_default().push(a);
+                }
+            }
+        }
+        accented
+    });
+    accented.get(&c).map_or(&[], Vec::as_slice)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_letter_case() {
-        assert!(!is_lower_case(b'`'));
-        assert!(is_lower_case(b'a'));
-        assert!(is_lower_case(b'z'));
-        assert!(!is_lower_case(b'{'));
-
-        assert!(!is_upper_case(b'@'));
-        assert!(is_upper_case(b'A'));
-        assert!(is_upper_case(b'Z'));
-        assert!(!is_upper_case(b'['));
-
-        assert_eq!(to_lower_case(b'A'), Some(b'a'));
-        assert_eq!(to_lower_case(b'Z'), Some(b'z'));
-        assert_eq!(to_lower_case(b'a'), None);
-        assert_eq!(to_lower_case(b'z'), None);
-
-        assert_eq!(to_upper_case(b'A'), None);
-        assert_eq!(to_upper_case(b'Z'), None);
-        assert_eq!(to_upper_case(b'a'), Some(b'A'));
-        assert_eq!(to_upper_case(b'z'), Some(b'Z'));
+        assert!(!is_upper_case('@'));
+        assert!(is_upper_case('A'));
+        assert!(is_upper_case('Z'));
+        assert!(!is_upper_case('['));
+        assert!(is_upper_case('É'));
+        assert!(!is_upper_case('é'));
+        assert!(!is_upper_case('ß'));
+
+        assert_eq!(to_lower_case('A'), "a");
+        assert_eq!(to_lower_case('Z'), "z");
+        assert_eq!(to_lower_case('a'), "a");
+        assert_eq!(to_lower_case('É'), "é");
+        assert_eq!(to_lower_case('İ'), "i\u{307}");
+
+        assert_eq!(to_upper_case('a'), "A");
+        assert_eq!(to_upper_case('z'), "Z");
+        assert_eq!(to_upper_case('A'), "A");
+        assert_eq!(to_upper_case('é'), "É");
+        assert_eq!(to_upper_case('ß'), "SS");
+    }
+
+    #[test]
+    fn test_accents() {
+        assert_eq!(base_letter('é'), 'e');
+        assert_eq!(base_letter('Ü'), 'U');
+        assert_eq!(base_letter('e'), 'e');
+        assert_eq!(base_letter('ß'), 'ß');
+
+        assert!(accented_letters('e').contains(&'é'));
+        assert!(accented_letters('e').contains(&'è'));
+        assert!(accented_letters('u').contains(&'ü'));
+        assert!(!accented_letters('e').contains(&'É'));
+        assert!(accented_letters('é').is_empty());
     }
 }